@@ -0,0 +1,185 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::exit;
+
+mod registry;
+mod table;
+
+use registry::Day;
+use table::Row;
+
+const USAGE: &str = "\
+Usage: aoc run [--day <n>] [--part <1|2>] [--inputs <dir>] [input]
+
+Runs the requested day and part, or every registered day when --day is omitted.
+Without an explicit input file each day reads <dir>/day-<n>/input.txt, where <dir>
+defaults to the current directory.";
+
+#[derive(Debug, PartialEq, Eq)]
+struct RunOptions {
+    day: Option<u8>,
+    part: Option<u8>,
+    inputs: PathBuf,
+    input: Option<PathBuf>,
+}
+
+fn parse_args(args: &[String]) -> Result<RunOptions, String> {
+    let mut args = args.iter();
+
+    match args.next().map(String::as_str) {
+        Some("run") => {}
+        Some(other) => return Err(format!("Unknown command '{}'", other)),
+        None => return Err("Missing command".to_string()),
+    }
+
+    let mut options = RunOptions {
+        day: None,
+        part: None,
+        inputs: PathBuf::from("."),
+        input: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number(args.next(), "--day")?),
+            "--part" => {
+                let part = parse_number(args.next(), "--part")?;
+                if !(1..=2).contains(&part) {
+                    return Err(format!("Part must be 1 or 2, got {}", part));
+                }
+                options.part = Some(part);
+            }
+            "--inputs" => {
+                let dir = args.next().ok_or("Missing value for --inputs")?;
+                options.inputs = PathBuf::from(dir);
+            }
+            "--all" => options.day = None,
+            flag if flag.starts_with("--") => return Err(format!("Unknown flag '{}'", flag)),
+            path => options.input = Some(PathBuf::from(path)),
+        }
+    }
+
+    if options.input.is_some() && options.day.is_none() {
+        return Err("An input file can only be given together with --day".to_string());
+    }
+
+    Ok(options)
+}
+
+fn parse_number(value: Option<&String>, flag: &str) -> Result<u8, String> {
+    let value = value.ok_or(format!("Missing value for {}", flag))?;
+    value
+        .parse::<u8>()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, flag))
+}
+
+fn run_day(day: &Day, options: &RunOptions) -> Vec<Row> {
+    let path = options
+        .input
+        .clone()
+        .unwrap_or_else(|| options.inputs.join(format!("day-{}", day.number)).join("input.txt"));
+
+    let parts: Vec<u8> = match options.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let input = fs::read_to_string(&path);
+
+    parts
+        .into_iter()
+        .map(|part| {
+            let answer = match &input {
+                Err(e) => format!("error: {}: {}", path.display(), e),
+                Ok(input) => match (day.solve)(input, part) {
+                    Ok(answer) => answer,
+                    Err(e) => format!("error: {}", e),
+                },
+            };
+            Row { day: day.number, part, answer }
+        })
+        .collect()
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            exit(2)
+        }
+    };
+
+    let days: Vec<&Day> = match options.day {
+        Some(number) => match registry::find(number) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("Day {} has not been solved", number);
+                exit(2)
+            }
+        },
+        None => registry::DAYS.iter().collect(),
+    };
+
+    let rows: Vec<Row> = days.iter().flat_map(|day| run_day(day, &options)).collect();
+
+    print!("{}", table::render(&rows));
+
+    if rows.iter().any(|row| row.answer.starts_with("error: ")) {
+        exit(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_single_day_and_part() {
+        assert_eq!(
+            parse_args(&args("run --day 5 --part 2 input.txt")),
+            Ok(RunOptions {
+                day: Some(5),
+                part: Some(2),
+                inputs: PathBuf::from("."),
+                input: Some(PathBuf::from("input.txt")),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_all_days() {
+        assert_eq!(
+            parse_args(&args("run --all --inputs puzzles")),
+            Ok(RunOptions {
+                day: None,
+                part: None,
+                inputs: PathBuf::from("puzzles"),
+                input: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_input_without_day() {
+        assert_eq!(parse_args(&args("run input.txt")).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_part() {
+        assert_eq!(parse_args(&args("run --day 1 --part 3")).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_requires_run_command() {
+        assert_eq!(parse_args(&args("--day 1")).is_err(), true);
+    }
+}
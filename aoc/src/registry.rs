@@ -0,0 +1,43 @@
+use common::Solution;
+
+/// Parses the raw puzzle input and answers the requested part.
+pub type Solver = fn(&str, u8) -> common::Result<String>;
+
+pub struct Day {
+    pub number: u8,
+    pub solve: Solver,
+}
+
+pub static DAYS: &[Day] = &[
+    Day { number: 1, solve: day_1::Day1::solve },
+    Day { number: 2, solve: day_2::Day2::solve },
+    Day { number: 3, solve: day_3::Day3::solve },
+    Day { number: 4, solve: day_4::Day4::solve },
+    Day { number: 5, solve: day_5::Day5::solve },
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_days_are_registered_in_order() {
+        let numbers: Vec<u8> = DAYS.iter().map(|day| day.number).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_find_unknown_day() {
+        assert_eq!(find(25).is_none(), true);
+    }
+
+    #[test]
+    fn test_solver_rejects_part_out_of_range() {
+        assert_eq!((find(1).unwrap().solve)("1000\n\n", 3).is_err(), true);
+    }
+}
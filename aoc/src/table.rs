@@ -0,0 +1,55 @@
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+}
+
+/// Render rows as a table, underlining the header to the width of the widest answer.
+pub fn render(rows: &[Row]) -> String {
+    let width = rows
+        .iter()
+        .map(|row| row.answer.len())
+        .max()
+        .unwrap_or(0)
+        .max("Answer".len());
+
+    let mut out = String::from("Day | Part | Answer\n");
+    out.push_str(&format!("----+------+-{}\n", "-".repeat(width)));
+    for row in rows {
+        out.push_str(&format!(
+            "{:>3} | {:>4} | {}\n",
+            row.day, row.part, row.answer
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_render_underlines_to_widest_answer() {
+        let rows = vec![
+            Row { day: 1, part: 1, answer: "24000".to_string() },
+            Row { day: 5, part: 2, answer: "MCDNPQRST".to_string() },
+        ];
+
+        assert_eq!(
+            render(&rows),
+            "Day | Part | Answer\n\
+             ----+------+----------\n  \
+               1 |    1 | 24000\n  \
+               5 |    2 | MCDNPQRST\n"
+        );
+    }
+
+    #[test]
+    fn test_render_empty_table_has_header() {
+        assert_eq!(
+            render(&[]),
+            "Day | Part | Answer\n----+------+-------\n"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+use std::io;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read.
+    Io(io::Error),
+    /// The input is malformed. Lines and columns count from 1.
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// The input is well formed but cannot be solved.
+    Semantic(String),
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, text: impl Into<String>, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line,
+            column,
+            text: text.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Move a parse error produced from a single line to where that line sits in the whole input.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Error::Parse { column, text, message, .. } => Error::Parse { line, column, text, message },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "unable to read input: {}", e),
+            Error::Parse { line, column, text, message } => {
+                write!(f, "line {}, column {}: {} (found '{}')", line, column, message, text)
+            }
+            Error::Semantic(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_error_display_includes_position_and_text() {
+        let error = Error::parse(3, 7, "x1", "expected a number");
+
+        assert_eq!(error.to_string(), "line 3, column 7: expected a number (found 'x1')");
+    }
+
+    #[test]
+    fn test_at_line_moves_parse_error() {
+        let error = Error::parse(1, 2, "?", "bad").at_line(12);
+
+        assert_eq!(error.to_string(), "line 12, column 2: bad (found '?')");
+    }
+
+    #[test]
+    fn test_at_line_keeps_other_errors() {
+        let error = Error::Semantic("unsolvable".to_string()).at_line(4);
+
+        assert_eq!(error.to_string(), "unsolvable");
+    }
+}
@@ -3,6 +3,14 @@ use std::io;
 use std::io::BufRead;
 use std::path::Path;
 
+mod error;
+mod solution;
+mod top_k;
+
+pub use error::{Error, Result};
+pub use solution::{run, run_file, Solution};
+pub use top_k::{top_k, TopK};
+
 pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
     where
         P: AsRef<Path>,
@@ -11,6 +19,13 @@ pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
     Ok(io::BufReader::new(file).lines())
 }
 
+/// Split a line on whitespace, pairing each token with the column it starts at.
+pub fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    // split_whitespace hands back slices of `line`, so the offset of each slice is its column.
+    line.split_whitespace()
+        .map(move |token| (token.as_ptr() as usize - line.as_ptr() as usize + 1, token))
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -42,4 +57,11 @@ mod tests {
         }
         assert_eq!(count, 5)
     }
+
+    #[test]
+    fn test_tokens_report_columns() {
+        let found: Vec<(usize, &str)> = tokens("move  12 from 3").collect();
+
+        assert_eq!(found, vec![(1, "move"), (7, "12"), (10, "from"), (15, "3")]);
+    }
 }
@@ -0,0 +1,111 @@
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::process::exit;
+
+use crate::{Error, Result};
+
+/// A puzzle solution, split into parsing the raw input and answering each part from it.
+pub trait Solution {
+    type Input;
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer>;
+
+    /// Parse the raw input and answer the requested part.
+    fn solve(input: &str, part: u8) -> Result<String> {
+        let input = Self::parse(input)?;
+        let answer = match part {
+            1 => Self::part1(&input)?,
+            2 => Self::part2(&input)?,
+            _ => return Err(Error::Semantic(format!("Part must be 1 or 2, got {}", part))),
+        };
+        Ok(answer.to_string())
+    }
+}
+
+/// Entry point shared by the day binaries: solve both parts for the file named on the
+/// command line and print the answers. Exits with a non-zero code if either part fails.
+pub fn run<S: Solution>() {
+    let args: Vec<String> = env::args().collect();
+
+    let Some(file_path) = args.get(1) else {
+        eprintln!("Usage: {} <input>", args[0]);
+        exit(2)
+    };
+
+    run_file::<S>(file_path, S::parse)
+}
+
+/// The body of [`run`], for binaries that parse their own command line: solve both parts for
+/// `file_path`, reading its contents with `parse`, and print the answers.
+pub fn run_file<S: Solution>(file_path: &str, parse: impl FnOnce(&str) -> Result<S::Input>) {
+    println!("In file {}", file_path);
+
+    let input = match fs::read_to_string(file_path)
+        .map_err(Error::from)
+        .and_then(|input| parse(&input))
+    {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {}: {}", file_path, e);
+            exit(1)
+        }
+    };
+
+    let mut failed = false;
+    for (part, answer) in [(1, S::part1(&input)), (2, S::part2(&input))] {
+        match answer {
+            Ok(answer) => println!("Part {}: {}", part, answer),
+            Err(e) => {
+                eprintln!("Part {}: error: {}", part, e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        exit(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    struct LineCount;
+
+    impl Solution for LineCount {
+        type Input = Vec<String>;
+        type Answer = usize;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input.lines().map(String::from).collect())
+        }
+
+        fn part1(input: &Self::Input) -> Result<Self::Answer> {
+            Ok(input.len())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Self::Answer> {
+            Ok(input.iter().filter(|line| line.is_empty()).count())
+        }
+    }
+
+    #[test]
+    fn test_solve_answers_requested_part() {
+        assert_eq!(LineCount::solve("a\n\nb", 1).unwrap(), "3");
+        assert_eq!(LineCount::solve("a\n\nb", 2).unwrap(), "1");
+    }
+
+    #[test]
+    fn test_solve_rejects_unknown_part() {
+        assert_eq!(LineCount::solve("a", 3).is_err(), true);
+    }
+}
@@ -0,0 +1,100 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Keeps the `k` largest items pushed into it, holding at most `k` at a time. The smallest item
+/// kept sits at the top of a min-heap so each push is a single comparison when it loses.
+pub struct TopK<T> {
+    k: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        // The heap grows as items arrive, so a huge `k` costs nothing up front.
+        Self {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if item > smallest.0 {
+                smallest.0 = item;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The items kept, largest first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap.into_sorted_vec().into_iter().map(|Reverse(item)| item).collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+/// The `k` largest items, largest first, without collecting the whole iterator.
+pub fn top_k<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    top.extend(items);
+    top.into_sorted_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_top_k_returns_largest_first() {
+        assert_eq!(top_k(vec![4, 9, 1, 7, 3, 8], 3), vec![9, 8, 7]);
+    }
+
+    #[test]
+    fn test_top_k_returns_everything_when_short_of_items() {
+        assert_eq!(top_k(vec![2, 5], 3), vec![5, 2]);
+    }
+
+    #[test]
+    fn test_top_k_of_zero_keeps_nothing() {
+        assert_eq!(top_k(vec![2, 5], 0), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_top_k_never_holds_more_than_k() {
+        let mut top = TopK::new(2);
+        for item in 0..1000 {
+            top.push(item);
+            assert_eq!(top.len() <= 2, true);
+        }
+
+        assert_eq!(top.into_sorted_vec(), vec![999, 998]);
+    }
+
+    #[test]
+    fn test_top_k_of_huge_k_allocates_as_it_goes() {
+        assert_eq!(top_k(vec![2, 5, 4], usize::MAX), vec![5, 4, 2]);
+    }
+
+    #[test]
+    fn test_top_k_keeps_duplicates() {
+        assert_eq!(top_k(vec![3, 3, 1, 3], 3), vec![3, 3, 3]);
+    }
+}
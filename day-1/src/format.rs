@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use common::{read_lines, Error, Result};
+use serde_json::Value;
+
+use crate::{parse_elves, Elf};
+
+/// The layouts an inventory can be written in.
+///
+/// - `Text` is the puzzle's own: one calorie count per line, elves separated by blank lines.
+/// - `Csv` has an `elf_id,calories` row per item, with an optional header. Rows with the same
+///   elf id belong to the same elf, wherever they appear.
+/// - `Json` is an array holding either an array of calorie counts per elf, or an
+///   `{"elf_id": .., "calories": ..}` object per item like the CSV rows.
+///
+/// Elves are numbered in the order they first appear, whatever ids the input gives them.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InputFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+impl InputFormat {
+    /// Guess the format from the first thing in the input: an opening bracket is JSON, a comma
+    /// on the first line is CSV, and anything else is text.
+    pub fn detect(input: &str) -> Self {
+        let input = input.trim_start();
+        if input.starts_with('[') {
+            InputFormat::Json
+        } else if input.lines().next().is_some_and(|line| line.contains(',')) {
+            InputFormat::Csv
+        } else {
+            InputFormat::Text
+        }
+    }
+
+    /// [`InputFormat::detect`] on a file, reading only as far as its first line with anything
+    /// on it.
+    pub fn detect_file(path: impl AsRef<Path>) -> Result<Self> {
+        for line in read_lines(path)? {
+            let line = line?;
+            if !line.trim().is_empty() {
+                return Ok(Self::detect(&line));
+            }
+        }
+        Ok(InputFormat::Text)
+    }
+
+    pub fn parse(self, input: &str) -> Result<Vec<Elf>> {
+        match self {
+            InputFormat::Text => parse_elves(input),
+            InputFormat::Csv => parse_csv(input),
+            InputFormat::Json => parse_json(input),
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(InputFormat::Text),
+            "csv" => Ok(InputFormat::Csv),
+            "json" => Ok(InputFormat::Json),
+            other => Err(format!("expected an input format of text, csv or json, found '{}'", other)),
+        }
+    }
+}
+
+/// Collects items into elves keyed by the input's own elf ids.
+#[derive(Default)]
+struct Grouper {
+    ids: HashMap<String, usize>,
+    elves: Vec<Elf>,
+}
+
+impl Grouper {
+    /// The elf with the given id, starting a new one the first time the id is seen.
+    fn elf(&mut self, id: &str) -> &mut Elf {
+        let index = match self.ids.get(id) {
+            Some(&index) => index,
+            None => {
+                self.ids.insert(id.to_string(), self.elves.len());
+                self.new_elf()
+            }
+        };
+        &mut self.elves[index]
+    }
+
+    /// Start an elf that has no id in the input, so no later row can add to it.
+    fn anonymous(&mut self) -> &mut Elf {
+        let index = self.new_elf();
+        &mut self.elves[index]
+    }
+
+    fn new_elf(&mut self) -> usize {
+        self.elves.push(Elf::new(self.elves.len()));
+        self.elves.len() - 1
+    }
+}
+
+fn parse_csv(input: &str) -> Result<Vec<Elf>> {
+    let mut elves = Grouper::default();
+    let mut first_row = true;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // The header can only be the first row with anything on it.
+        let header = first_row && line.trim().replace(' ', "") == "elf_id,calories";
+        first_row = false;
+        if header {
+            continue;
+        }
+
+        let start = line.len() - line.trim_start().len() + 1;
+        let Some((id, meal)) = line.split_once(',') else {
+            return Err(Error::parse(i + 1, start, line.trim(), "expected a row of elf_id,calories"));
+        };
+        let id = id.trim();
+        if id.is_empty() {
+            return Err(Error::parse(i + 1, start, line.trim(), "expected an elf id"));
+        }
+
+        let column = line.len() - meal.trim_start().len() + 1;
+        let meal = meal.trim();
+        let calories = meal
+            .parse::<u64>()
+            .map_err(|_| Error::parse(i + 1, column, meal, "expected a calorie count"))?;
+        elves
+            .elf(id)
+            .add_calories(calories)
+            .map_err(|e| Error::parse(i + 1, column, meal, e.to_string()))?;
+    }
+
+    Ok(elves.elves)
+}
+
+fn parse_json(input: &str) -> Result<Vec<Elf>> {
+    let value: Value = serde_json::from_str(input)
+        .map_err(|e| Error::parse(e.line(), e.column(), "", format!("invalid JSON: {}", e)))?;
+
+    let Value::Array(entries) = value else {
+        return Err(Error::Semantic("expected a JSON array of elves".to_string()));
+    };
+
+    let mut elves = Grouper::default();
+    for (i, entry) in entries.iter().enumerate() {
+        match entry {
+            Value::Array(items) => {
+                let elf = elves.anonymous();
+                for item in items {
+                    elf.add_calories(calories(item, i)?)?;
+                }
+            }
+            Value::Object(row) => {
+                let id = match row.get("elf_id") {
+                    Some(Value::String(id)) => id.clone(),
+                    Some(Value::Number(id)) => id.to_string(),
+                    _ => return Err(entry_error(i, "expected an elf_id")),
+                };
+                let item = row.get("calories").ok_or_else(|| entry_error(i, "expected calories"))?;
+                elves.elf(&id).add_calories(calories(item, i)?)?;
+            }
+            _ => return Err(entry_error(i, "expected an array of calories or an object")),
+        }
+    }
+
+    Ok(elves.elves)
+}
+
+fn calories(value: &Value, entry: usize) -> Result<u64> {
+    value
+        .as_u64()
+        .ok_or_else(|| entry_error(entry, &format!("expected a calorie count, found {}", value)))
+}
+
+fn entry_error(entry: usize, message: &str) -> Error {
+    Error::Semantic(format!("JSON entry {}: {}", entry + 1, message))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn calories(elves: &[Elf]) -> Vec<u64> {
+        elves.iter().map(|elf| elf.calories()).collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(InputFormat::detect("1000\n2000\n"), InputFormat::Text);
+        assert_eq!(InputFormat::detect("elf_id,calories\n1,1000\n"), InputFormat::Csv);
+        assert_eq!(InputFormat::detect("  \n[[1000]]"), InputFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_format_name() {
+        assert_eq!("csv".parse::<InputFormat>(), Ok(InputFormat::Csv));
+        assert_eq!("xml".parse::<InputFormat>().is_err(), true);
+    }
+
+    #[test]
+    fn test_csv_groups_rows_by_elf_id() {
+        let elves = InputFormat::Csv
+            .parse("elf_id,calories\n7,1000\n3,4000\n7,2000\n")
+            .unwrap();
+
+        assert_eq!(calories(&elves), vec![3000, 4000]);
+        assert_eq!(elves[0].items(), &[1000, 2000]);
+        assert_eq!(elves[1].id(), 1);
+    }
+
+    #[test]
+    fn test_csv_without_header() {
+        let elves = InputFormat::Csv.parse("1, 1000\n2, 5\n").unwrap();
+
+        assert_eq!(calories(&elves), vec![1000, 5]);
+    }
+
+    #[test]
+    fn test_csv_reports_position_of_bad_count() {
+        let error = InputFormat::Csv.parse("1,1000\n2,lots\n").err().unwrap();
+
+        assert_eq!(error.to_string(), "line 2, column 3: expected a calorie count (found 'lots')");
+    }
+
+    #[test]
+    fn test_csv_header_after_blank_lines() {
+        let input = "\n  \nelf_id, calories\n1,1000\n";
+
+        assert_eq!(InputFormat::detect(input), InputFormat::Csv);
+        assert_eq!(calories(&InputFormat::Csv.parse(input).unwrap()), vec![1000]);
+    }
+
+    #[test]
+    fn test_csv_header_only_on_the_first_row() {
+        let error = InputFormat::Csv.parse("1,1000\nelf_id,calories\n").err().unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 8: expected a calorie count (found 'calories')"
+        );
+    }
+
+    #[test]
+    fn test_csv_reports_columns_of_indented_rows() {
+        let error = InputFormat::Csv.parse("1,1000\n    2, lots\n").err().unwrap();
+
+        assert_eq!(error.to_string(), "line 2, column 8: expected a calorie count (found 'lots')");
+        assert_eq!(
+            InputFormat::Csv.parse("  1000\n").err().unwrap().to_string(),
+            "line 1, column 3: expected a row of elf_id,calories (found '1000')"
+        );
+    }
+
+    #[test]
+    fn test_json_array_of_elves() {
+        let elves = InputFormat::Json.parse("[[1000, 2000], [], [4000]]").unwrap();
+
+        assert_eq!(calories(&elves), vec![3000, 0, 4000]);
+    }
+
+    #[test]
+    fn test_json_array_of_rows() {
+        let input = r#"[
+            {"elf_id": 1, "calories": 1000},
+            {"elf_id": "two", "calories": 5},
+            {"elf_id": 1, "calories": 1}
+        ]"#;
+        let elves = InputFormat::Json.parse(input).unwrap();
+
+        assert_eq!(calories(&elves), vec![1001, 5]);
+    }
+
+    #[test]
+    fn test_json_array_elves_are_kept_apart_from_ids() {
+        let input = r##"[[1000], {"elf_id": "#0", "calories": 5}]"##;
+        let elves = InputFormat::Json.parse(input).unwrap();
+
+        assert_eq!(calories(&elves), vec![1000, 5]);
+    }
+
+    #[test]
+    fn test_json_rejects_negative_counts() {
+        assert_eq!(InputFormat::Json.parse("[[1000, -1]]").is_err(), true);
+    }
+
+    #[test]
+    fn test_json_reports_syntax_errors() {
+        assert_eq!(InputFormat::Json.parse("[[1000,").is_err(), true);
+    }
+
+    #[test]
+    fn test_every_format_gives_the_same_elves() {
+        let text = InputFormat::Text.parse("1000\n2000\n\n4000").unwrap();
+        let csv = InputFormat::Csv.parse("a,1000\na,2000\nb,4000").unwrap();
+        let json = InputFormat::Json.parse("[[1000, 2000], [4000]]").unwrap();
+
+        assert_eq!(calories(&text), calories(&csv));
+        assert_eq!(calories(&text), calories(&json));
+    }
+}
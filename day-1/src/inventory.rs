@@ -0,0 +1,196 @@
+use std::io;
+
+use common::{top_k, Error, Result, TopK};
+
+use crate::Elf;
+
+/// Groups lines of calorie counts into elves. Each run of blank lines ends an elf, and the last
+/// elf is kept whether or not the input ends with a blank line. Whitespace around a count is
+/// ignored, so a line holding only spaces counts as blank.
+pub struct Inventory<I> {
+    lines: I,
+    line: usize,
+    next_id: usize,
+}
+
+impl<I> Inventory<I>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    pub fn new(lines: I) -> Self {
+        Self {
+            lines,
+            line: 0,
+            next_id: 0,
+        }
+    }
+}
+
+impl<I> Iterator for Inventory<I>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    type Item = Result<Elf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut current: Option<Elf> = None;
+
+        for line in self.lines.by_ref() {
+            self.line += 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            let meal = line.trim();
+            if meal.is_empty() {
+                if current.is_some() {
+                    break;
+                }
+                continue;
+            }
+
+            let column = line.len() - line.trim_start().len() + 1;
+            let at = |message: String| Some(Err(Error::parse(self.line, column, meal, message)));
+
+            let calories = match meal.parse::<u64>() {
+                Ok(calories) => calories,
+                Err(_) if meal.starts_with('-') && meal[1..].parse::<u64>().is_ok() => {
+                    return at("calorie counts cannot be negative".to_string());
+                }
+                Err(_) => return at("expected a calorie count".to_string()),
+            };
+
+            let elf = current.get_or_insert_with(|| {
+                self.next_id += 1;
+                Elf::new(self.next_id - 1)
+            });
+            if let Err(e) = elf.add_calories(calories) {
+                return at(e.to_string());
+            }
+        }
+
+        current.map(Ok)
+    }
+}
+
+/// Up to `n` elves carrying the most calories, most first. Returns fewer when there are not
+/// enough elves.
+pub fn top_n(elves: &[Elf], n: usize) -> Vec<Elf> {
+    top_k(elves.iter().cloned(), n)
+}
+
+/// Like [`top_n`], but reads elves straight from the parser so only `n` of them are held at
+/// once. Stops at the first elf that fails to parse.
+pub fn stream_top_n<I>(elves: I, n: usize) -> Result<Vec<Elf>>
+where
+    I: Iterator<Item = Result<Elf>>,
+{
+    let mut top = TopK::new(n);
+    for elf in elves {
+        top.push(elf?);
+    }
+    Ok(top.into_sorted_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn inventory(input: &str) -> Result<Vec<Elf>> {
+        Inventory::new(input.lines().map(|line| Ok(line.to_string()))).collect()
+    }
+
+    fn calories(elves: &[Elf]) -> Vec<u64> {
+        elves.iter().map(|elf| elf.calories()).collect()
+    }
+
+    #[test]
+    fn test_inventory_keeps_last_elf_without_trailing_blank_line() {
+        let elves = inventory("1000\n2000\n\n3000").unwrap();
+
+        assert_eq!(calories(&elves), vec![3000, 3000]);
+    }
+
+    #[test]
+    fn test_inventory_treats_runs_of_blank_lines_as_one_separator() {
+        let elves = inventory("\n\n1000\n\n\n  \n2000\n\n\n").unwrap();
+
+        assert_eq!(calories(&elves), vec![1000, 2000]);
+        assert_eq!(elves.iter().map(|elf| elf.id()).collect::<Vec<usize>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_inventory_ignores_whitespace_around_counts() {
+        let elves = inventory("  1000 \r\n2000\t\n").unwrap();
+
+        assert_eq!(calories(&elves), vec![3000]);
+    }
+
+    #[test]
+    fn test_inventory_reports_position_of_bad_count() {
+        let error = inventory("1000\n\n  abc\n").err().unwrap();
+
+        assert_eq!(error.to_string(), "line 3, column 3: expected a calorie count (found 'abc')");
+    }
+
+    #[test]
+    fn test_inventory_rejects_negative_counts() {
+        let error = inventory("1000\n-20\n").err().unwrap();
+
+        assert_eq!(error.to_string(), "line 2, column 1: calorie counts cannot be negative (found '-20')");
+    }
+
+    #[test]
+    fn test_inventory_reports_overflowing_elf() {
+        let error = inventory(&format!("1\n\n{}\n1\n", u64::MAX)).err().unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            format!("line 4, column 1: Elf 2 carries more than {} calories (found '1')", u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_inventory_passes_on_read_errors() {
+        let lines = vec![Ok("1000".to_string()), Err(io::Error::other("disk gone"))];
+        let elves: Result<Vec<Elf>> = Inventory::new(lines.into_iter()).collect();
+
+        assert_eq!(elves.is_err(), true);
+    }
+
+    #[test]
+    fn test_top_n_returns_most_calories_first() {
+        let elves = inventory("1\n\n3\n\n2\n").unwrap();
+
+        assert_eq!(calories(&top_n(&elves, 2)), vec![3, 2]);
+    }
+
+    #[test]
+    fn test_top_n_returns_fewer_when_short_of_elves() {
+        let elves = inventory("1\n\n3\n").unwrap();
+
+        assert_eq!(calories(&top_n(&elves, 3)), vec![3, 1]);
+        assert_eq!(top_n(&[], 3).len(), 0);
+    }
+
+    #[test]
+    fn test_stream_top_n_matches_top_n() {
+        let input = "1\n\n5\n\n3\n4\n\n2\n";
+        let lines = input.lines().map(|line| Ok(line.to_string()));
+
+        let streamed = stream_top_n(Inventory::new(lines), 2).unwrap();
+
+        assert_eq!(calories(&streamed), calories(&top_n(&inventory(input).unwrap(), 2)));
+        assert_eq!(calories(&streamed), vec![7, 5]);
+    }
+
+    #[test]
+    fn test_stream_top_n_fails_on_bad_elf() {
+        let lines = "1\n\nx\n".lines().map(|line| Ok(line.to_string()));
+
+        assert_eq!(stream_top_n(Inventory::new(lines), 2).is_err(), true);
+    }
+}
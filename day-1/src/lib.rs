@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+
+use common::{Error, Result, Solution};
+
+mod format;
+mod inventory;
+mod plan;
+mod stats;
+
+pub use format::InputFormat;
+pub use inventory::{stream_top_n, top_n, Inventory};
+pub use plan::{plan_balance, plan_limit, Move, Plan};
+pub use stats::{Bucket, Report};
+
+#[derive(Eq, Clone, Debug)]
+pub struct Elf {
+    id: usize,
+    items: Vec<u64>,
+    /// Sum of `items`, kept up to date as they come and go.
+    calories: u64,
+}
+
+impl Elf {
+    pub fn new(id: usize) -> Self {
+        Self { id, items: vec![], calories: 0 }
+    }
+
+    /// Add a meal to the elf's pack. Fails, leaving the elf as it was, if the total would
+    /// overflow.
+    pub fn add_calories(&mut self, meal: u64) -> Result<()> {
+        let Some(calories) = self.calories.checked_add(meal) else {
+            return Err(Error::Semantic(format!(
+                "Elf {} carries more than {} calories",
+                self.id + 1,
+                u64::MAX
+            )));
+        };
+        self.items.push(meal);
+        self.calories = calories;
+        Ok(())
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Calories of every item the elf carries. Cannot overflow, as `add_calories` refuses items
+    /// that would take the total past `u64::MAX`.
+    pub fn calories(&self) -> u64 {
+        self.calories
+    }
+
+    /// The calories of each item, in the order they were packed.
+    pub fn items(&self) -> &[u64] {
+        &self.items
+    }
+
+    pub fn largest_item(&self) -> Option<u64> {
+        self.items.iter().copied().max()
+    }
+
+    /// Take the item at `index` out of this elf's pack and give it to `other`. Both elves are
+    /// left as they were if the item does not exist or `other` cannot carry it.
+    pub fn transfer(&mut self, index: usize, other: &mut Elf) -> Result<u64> {
+        let Some(&meal) = self.items.get(index) else {
+            return Err(Error::Semantic(format!(
+                "Elf {} has no item {}, it carries {}",
+                self.id + 1,
+                index + 1,
+                self.items.len()
+            )));
+        };
+        other.add_calories(meal)?;
+        self.items.remove(index);
+        self.calories -= meal;
+        Ok(meal)
+    }
+}
+
+impl Ord for Elf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.calories().cmp(&other.calories())
+    }
+}
+
+impl PartialOrd for Elf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Elf {
+    fn eq(&self, other: &Self) -> bool {
+        self.calories() == other.calories()
+    }
+}
+
+pub fn parse_elves(input: &str) -> Result<Vec<Elf>> {
+    Inventory::new(input.lines().map(|line| Ok(line.to_string()))).collect()
+}
+
+pub fn calculate_best_elf(elves: &[Elf]) -> &Elf {
+    elves.iter().max().unwrap()
+}
+
+/// The elf carrying the single item with the most calories, with that item's calories. Ties
+/// go to the first elf in the input.
+pub fn largest_snack(elves: &[Elf]) -> Option<(&Elf, u64)> {
+    elves
+        .iter()
+        .rev()
+        .filter_map(|elf| Some((elf, elf.largest_item()?)))
+        .max_by_key(|&(_, calories)| calories)
+}
+
+/// The elf carrying the most items, the first in the input if several carry as many.
+pub fn most_items(elves: &[Elf]) -> Option<&Elf> {
+    elves.iter().rev().max_by_key(|elf| elf.items().len())
+}
+
+/// Calories carried by all the given elves together, failing if the sum overflows.
+pub fn total_calories(elves: &[Elf]) -> Result<u64> {
+    elves
+        .iter()
+        .try_fold(0u64, |total, elf| total.checked_add(elf.calories()))
+        .ok_or_else(|| Error::Semantic(format!("The elves carry more than {} calories", u64::MAX)))
+}
+
+pub fn calculate_best_3_elves(elves: &[Elf]) -> Vec<Elf> {
+    top_n(elves, 3)
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<Elf>;
+    type Answer = u64;
+
+    /// Reads any of the [`InputFormat`]s, telling them apart by their first line.
+    fn parse(input: &str) -> Result<Self::Input> {
+        InputFormat::detect(input).parse(input)
+    }
+
+    /// Calories carried by the elf with the most food.
+    fn part1(elves: &Self::Input) -> Result<Self::Answer> {
+        if elves.is_empty() {
+            return Err(Error::Semantic("No elves on this trip!".to_string()));
+        }
+        Ok(calculate_best_elf(elves).calories())
+    }
+
+    /// Calories carried by the three elves with the most food.
+    fn part2(elves: &Self::Input) -> Result<Self::Answer> {
+        if elves.len() < 3 {
+            return Err(Error::Semantic(format!(
+                "Need at least 3 elves, found {}",
+                elves.len()
+            )));
+        }
+        total_calories(&calculate_best_3_elves(elves))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    #[test]
+    fn test_example_counts_the_final_elf() {
+        let elves = Day1::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day1::part1(&elves).unwrap(), 24000);
+        assert_eq!(Day1::part2(&elves).unwrap(), 45000);
+    }
+
+    #[test]
+    fn test_part2_errors_with_fewer_than_three_elves() {
+        let elves = Day1::parse("1000\n\n2000").unwrap();
+
+        assert_eq!(Day1::part2(&elves).is_err(), true);
+    }
+
+    #[test]
+    fn test_add_calories_fails_on_overflow() {
+        let mut elf = Elf::new(0);
+        elf.add_calories(u64::MAX - 1).unwrap();
+
+        assert_eq!(elf.add_calories(2).is_err(), true);
+        assert_eq!(elf.calories(), u64::MAX - 1);
+        assert_eq!(elf.items(), &[u64::MAX - 1]);
+    }
+
+    #[test]
+    fn test_part2_fails_when_top_three_overflow() {
+        let max = u64::MAX.to_string();
+        let elves = Day1::parse(&format!("{}\n\n{}\n\n1", max, max)).unwrap();
+
+        assert_eq!(Day1::part1(&elves).unwrap(), u64::MAX);
+        assert_eq!(Day1::part2(&elves).is_err(), true);
+    }
+
+    #[test]
+    fn test_calories_are_the_sum_of_items() {
+        let elves = Day1::parse(EXAMPLE).unwrap();
+
+        assert_eq!(elves[3].items(), &[7000, 8000, 9000]);
+        assert_eq!(elves[3].calories(), 24000);
+    }
+
+    #[test]
+    fn test_largest_snack() {
+        let elves = Day1::parse(EXAMPLE).unwrap();
+        let (elf, calories) = largest_snack(&elves).unwrap();
+
+        assert_eq!((elf.id(), calories), (4, 10000));
+    }
+
+    #[test]
+    fn test_most_items() {
+        let elves = Day1::parse(EXAMPLE).unwrap();
+
+        assert_eq!(most_items(&elves).unwrap().id(), 0);
+        assert_eq!(most_items(&[]).is_none(), true);
+    }
+
+    #[test]
+    fn test_transfer_moves_an_item() {
+        let mut elves = Day1::parse(EXAMPLE).unwrap();
+        let (left, right) = elves.split_at_mut(1);
+
+        assert_eq!(left[0].transfer(2, &mut right[0]).unwrap(), 3000);
+        assert_eq!(left[0].items(), &[1000, 2000]);
+        assert_eq!(right[0].items(), &[4000, 3000]);
+        assert_eq!((left[0].calories(), right[0].calories()), (3000, 7000));
+    }
+
+    #[test]
+    fn test_transfer_of_missing_item_fails() {
+        let mut from = Elf::new(0);
+        let mut to = Elf::new(1);
+
+        assert_eq!(from.transfer(0, &mut to).is_err(), true);
+    }
+
+    #[test]
+    fn test_transfer_that_would_overflow_leaves_both_elves() {
+        let mut from = Elf::new(0);
+        from.add_calories(2).unwrap();
+        let mut to = Elf::new(1);
+        to.add_calories(u64::MAX - 1).unwrap();
+
+        assert_eq!(from.transfer(0, &mut to).is_err(), true);
+        assert_eq!(from.items(), &[2]);
+        assert_eq!(to.items(), &[u64::MAX - 1]);
+    }
+}
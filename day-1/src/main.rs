@@ -1,104 +1,288 @@
-use std::cmp::Ordering;
 use std::env;
+use std::fs;
 use std::process::exit;
 
-use common::read_lines;
+use common::{read_lines, Error, Solution};
+use day_1::{
+    plan_balance, plan_limit, stream_top_n, top_n, total_calories, Day1, Elf, InputFormat,
+    Inventory, Report,
+};
 
-#[derive(Eq, Clone)]
-struct Elf {
-    id: i32,
-    calories: i32,
+const USAGE: &str = "\
+Usage: day-1 [--input-format <text|csv|json>] [--top <n>] [--stats] [--format <table|json>]
+             [--limit <calories> | --balance] <input>
+
+Prints the calories carried by the best elf and by the best three elves.
+
+  --input-format <format> read the input as text, csv or json rather than guessing from its start
+  --top <n>               print the n elves carrying the most calories, streaming text input
+  --stats                 print statistics of the calories every elf carries
+  --format <table|json>   how to print the statistics, a table by default
+  --limit <calories>      plan the fewest item moves that leave no elf over the limit
+  --balance               plan item moves that leave the heaviest elf as light as possible";
+
+#[derive(Debug, PartialEq)]
+enum Redistribute {
+    Limit(u64),
+    Balance,
+}
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Table,
+    Json,
 }
 
-impl Elf {
-    pub fn new(id: i32, calories: i32) -> Self {
-        Self { id, calories }
+struct Options {
+    input_format: Option<InputFormat>,
+    top: Option<usize>,
+    stats: Option<Format>,
+    redistribute: Option<Redistribute>,
+    input: String,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let mut input_format = None;
+    let mut top = None;
+    let mut stats = false;
+    let mut format = None;
+    let mut redistribute = None;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input-format" => {
+                input_format = Some(args.next().ok_or("Missing value for --input-format")?.parse()?);
+            }
+            "--top" => {
+                let n = args.next().ok_or("Missing value for --top")?;
+                top = Some(n.parse::<usize>().map_err(|_| format!("Invalid value '{}' for --top", n))?);
+            }
+            "--stats" => stats = true,
+            "--format" => {
+                format = match args.next().ok_or("Missing value for --format")?.as_str() {
+                    "table" => Some(Format::Table),
+                    "json" => Some(Format::Json),
+                    other => return Err(format!("Unknown format '{}'", other)),
+                }
+            }
+            "--limit" => {
+                let limit = args.next().ok_or("Missing value for --limit")?;
+                let limit = limit
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid value '{}' for --limit", limit))?;
+                redistribute = Some(Redistribute::Limit(limit));
+            }
+            "--balance" => redistribute = Some(Redistribute::Balance),
+            flag if flag.starts_with("--") => return Err(format!("Unknown flag '{}'", flag)),
+            path => input = Some(path.to_string()),
+        }
     }
 
-    pub fn add_calories(&mut self, meal: i32) {
-        self.calories = self.calories + meal;
+    if format.is_some() && !stats {
+        return Err("--format only applies to --stats".to_string());
     }
 
-    pub fn id(&self) -> i32 {
-        self.id
+    Ok(Options {
+        input_format,
+        top,
+        stats: stats.then(|| format.unwrap_or(Format::Table)),
+        redistribute,
+        input: input.ok_or("Missing input file")?,
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            exit(2)
+        }
+    };
+
+    if let Some(redistribute) = options.redistribute {
+        return print_plan(&options.input, options.input_format, redistribute);
+    }
+
+    if let Some(format) = options.stats {
+        return print_stats(&options.input, options.input_format, format);
+    }
+
+    if let Some(n) = options.top {
+        return print_top(&options.input, options.input_format, n);
     }
 
-    pub fn calories(&self) -> i32 {
-        self.calories
+    match options.input_format {
+        Some(format) => common::run_file::<Day1>(&options.input, |text| format.parse(text)),
+        None => common::run_file::<Day1>(&options.input, Day1::parse),
     }
 }
 
-impl Ord for Elf {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.calories.cmp(&other.calories)
+/// Read every elf from the file, in the given format or the one its contents suggest.
+fn read_elves(input: &str, format: Option<InputFormat>) -> common::Result<Vec<Elf>> {
+    let text = fs::read_to_string(input)?;
+    format.unwrap_or_else(|| InputFormat::detect(&text)).parse(&text)
+}
+
+fn fail(input: &str, e: Error) -> ! {
+    eprintln!("error: {}: {}", input, e);
+    exit(1)
+}
+
+/// The `n` elves carrying the most. Text input is streamed so only `n` elves are held at once;
+/// the other formats are read whole.
+fn top_elves(input: &str, format: Option<InputFormat>, n: usize) -> common::Result<Vec<Elf>> {
+    let format = match format {
+        Some(format) => format,
+        None => InputFormat::detect_file(input)?,
+    };
+    match format {
+        InputFormat::Text => stream_top_n(Inventory::new(read_lines(input)?), n),
+        format => read_elves(input, Some(format)).map(|elves| top_n(&elves, n)),
     }
 }
 
-impl PartialOrd for Elf {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+fn print_top(input: &str, format: Option<InputFormat>, n: usize) {
+    println!("In file {}", input);
+
+    let elves = top_elves(input, format, n).unwrap_or_else(|e| fail(input, e));
+    for elf in &elves {
+        println!("Elf {}: {}", elf.id() + 1, elf.calories());
     }
+    let total = total_calories(&elves).unwrap_or_else(|e| fail(input, e));
+    println!("Total: {}", total);
 }
 
-impl PartialEq for Elf {
-    fn eq(&self, other: &Self) -> bool {
-        self.calories == other.calories
+fn print_stats(input: &str, input_format: Option<InputFormat>, format: Format) {
+    let report = read_elves(input, input_format)
+        .and_then(|elves| Report::new(&elves))
+        .unwrap_or_else(|e| fail(input, e));
+
+    match format {
+        Format::Json => println!("{}", report.to_json()),
+        Format::Table => print!("{}", report),
     }
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    let file_path = &args[1];
-
-    let mut elves: Vec<Elf> = vec![];
-
-    println!("In file {}", file_path);
-
-    if let Ok(lines) = read_lines(file_path) {
-        let mut current: Elf = Elf::new(0, 0);
-        for line in lines {
-            match line.ok() {
-                Some(cal) => {
-                    if cal == "" {
-                        println!(
-                            "Add Elf {}, with {} calories of food",
-                            current.id, current.calories
-                        );
-                        elves.push(current.clone());
-                        current = Elf::new(current.id + 1, 0);
-                    } else {
-                        current.add_calories(cal.parse::<i32>().unwrap());
-                    }
-                }
-                None => print!("Found Nothing!"),
-            }
-        }
+fn print_plan(input: &str, input_format: Option<InputFormat>, redistribute: Redistribute) {
+    let plan = read_elves(input, input_format)
+        .and_then(|elves| match redistribute {
+            Redistribute::Limit(limit) => plan_limit(&elves, limit),
+            Redistribute::Balance => plan_balance(&elves),
+        })
+        .unwrap_or_else(|e| fail(input, e));
+
+    for step in &plan.moves {
+        println!("{}", step);
+    }
+    println!("{} moves, no elf carries more than {} calories", plan.moves.len(), plan.limit);
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_reads_top() {
+        let options = parse_args(&args(&["--top", "5", "input.txt"])).unwrap();
+
+        assert_eq!(options.top, Some(5));
+        assert_eq!(options.input, "input.txt");
     }
 
-    if elves.len() == 0 {
-        println!("No elves on this trip!");
-        exit(0)
+    #[test]
+    fn test_parse_args_rejects_bad_top() {
+        assert_eq!(parse_args(&args(&["--top", "many", "input.txt"])).is_err(), true);
     }
 
-    println!(
-        "The elf with the most food has {:?} calories",
-        calculate_best_elf(&elves).calories
-    );
+    #[test]
+    fn test_parse_args_requires_input() {
+        assert_eq!(parse_args(&args(&["--top", "5"])).is_err(), true);
+    }
 
-    let best_elves = calculate_best_3_elves(&elves);
-    println!(
-        "The top 3 elves have {:?} calories",
-        best_elves.iter().map(|x| x.calories).sum::<i32>()
-    )
-}
+    #[test]
+    fn test_parse_args_stats_defaults_to_table() {
+        let options = parse_args(&args(&["--stats", "input.txt"])).unwrap();
 
-fn calculate_best_elf(elves: &Vec<Elf>) -> &Elf {
-    elves.iter().max().unwrap()
-}
+        assert_eq!(options.stats, Some(Format::Table));
+    }
 
-fn calculate_best_3_elves<'a>(elves: &Vec<Elf>) -> Vec<Elf> {
-    let mut sorted = elves.clone();
-    sorted.sort_by_key(|x| -x.calories);
-    sorted[0..3].to_vec()
+    #[test]
+    fn test_parse_args_reads_stats_format() {
+        let options = parse_args(&args(&["--stats", "--format", "json", "input.txt"])).unwrap();
+
+        assert_eq!(options.stats, Some(Format::Json));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_format_without_stats() {
+        assert_eq!(parse_args(&args(&["--format", "json", "input.txt"])).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_args_reads_limit() {
+        let options = parse_args(&args(&["--limit", "60000", "input.txt"])).unwrap();
+
+        assert_eq!(options.redistribute, Some(Redistribute::Limit(60000)));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_negative_limit() {
+        assert_eq!(parse_args(&args(&["--limit", "-1", "input.txt"])).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_args_reads_input_format() {
+        let options = parse_args(&args(&["--input-format", "csv", "input.csv"])).unwrap();
+
+        assert_eq!(options.input_format, Some(InputFormat::Csv));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_unknown_input_format() {
+        assert_eq!(parse_args(&args(&["--input-format", "xml", "input.xml"])).is_err(), true);
+    }
+
+    /// Write `contents` to a file of its own in the temporary directory, returning its path.
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path = env::temp_dir().join(format!("day-1-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn top_calories(input: &str, format: Option<InputFormat>, n: usize) -> Vec<u64> {
+        top_elves(input, format, n).unwrap().iter().map(|elf| elf.calories()).collect()
+    }
+
+    #[test]
+    fn test_top_detects_csv() {
+        let input = temp_file("top.csv", "elf_id,calories\na,1000\nb,5000\na,2000\nc,4000\n");
+
+        assert_eq!(top_calories(&input, None, 2), vec![5000, 4000]);
+    }
+
+    #[test]
+    fn test_top_detects_json() {
+        let input = temp_file("top.json", "\n  [[1000, 2000], [5000], [4000]]");
+
+        assert_eq!(top_calories(&input, None, 2), vec![5000, 4000]);
+    }
+
+    #[test]
+    fn test_top_streams_text() {
+        let input = temp_file("top.txt", "1000\n2000\n\n5000\n\n4000\n");
+
+        assert_eq!(top_calories(&input, None, 2), vec![5000, 4000]);
+        assert_eq!(top_calories(&input, Some(InputFormat::Text), 1), vec![5000]);
+    }
 }
@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use common::{Error, Result};
+
+use crate::Elf;
+
+/// One item handed from one elf to another. Elves are given by their position in the list the
+/// plan was made for.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Move {
+    pub from: usize,
+    pub to: usize,
+    pub calories: u64,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Elf {} gives {} calories to elf {}", self.from + 1, self.calories, self.to + 1)
+    }
+}
+
+/// The moves that leave no elf carrying more than `limit` calories.
+#[derive(Debug, PartialEq)]
+pub struct Plan {
+    pub limit: u64,
+    pub moves: Vec<Move>,
+}
+
+impl Plan {
+    /// Carry out the moves on the elves the plan was made for.
+    pub fn apply(&self, elves: &mut [Elf]) -> Result<()> {
+        for step in &self.moves {
+            let index = elves[step.from]
+                .items()
+                .iter()
+                .position(|&item| item == step.calories)
+                .ok_or_else(|| Error::Semantic(format!("{}: no such item", step)))?;
+
+            let (from, to) = pair_mut(elves, step.from, step.to);
+            from.transfer(index, to)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plan the fewest item transfers that leave every elf carrying at most `limit` calories.
+///
+/// Any elf may give an item away, as long as the elf taking it is still within the limit
+/// once it has, so moves are made in order and a light elf can pass items on to make room
+/// for a heavier one. The plan is exact: a depth-first search over the moves, deepened one
+/// move at a time and cut short by how few items the overloaded elves must still give up.
+/// Whether the limit can be met at all is settled first, by trying every arrangement of the
+/// items the moves can reach once. Both are exponential in the worst case.
+pub fn plan_limit(elves: &[Elf], limit: u64) -> Result<Plan> {
+    check_limit(elves, limit)?;
+
+    let mut search = Search::new(elves, limit);
+    search.budget = search.needed.iter().sum();
+    while !search.place() {
+        search.searched.clear();
+        search.budget += 1;
+    }
+    Ok(Plan { limit, moves: search.moves })
+}
+
+fn check_limit(elves: &[Elf], limit: u64) -> Result<()> {
+    if let Some((elf, item)) = elves
+        .iter()
+        .enumerate()
+        .filter_map(|(i, elf)| Some((i, elf.largest_item()?)))
+        .find(|&(_, item)| item > limit)
+    {
+        return Err(Error::Semantic(format!(
+            "Elf {} carries an item of {} calories, over the limit of {}",
+            elf + 1,
+            item,
+            limit
+        )));
+    }
+
+    let total: u128 = elves.iter().map(|elf| elf.calories() as u128).sum();
+    if total > limit as u128 * elves.len() as u128 || !Search::new(elves, limit).reach() {
+        return Err(Error::Semantic(format!(
+            "No moves leave every elf carrying at most {} calories",
+            limit
+        )));
+    }
+    Ok(())
+}
+
+/// Where every item has got to, as `(elf it started on, calories, elf holding it)` for each
+/// item that has moved, in order.
+type Key = Vec<(usize, u64, usize)>;
+
+struct Search {
+    limit: u64,
+    /// Every elf's items, largest first, with the elf each started on.
+    items: Vec<Vec<(u64, usize)>>,
+    loads: Vec<u64>,
+    /// The fewest items each elf must still give up to get within the limit.
+    needed: Vec<usize>,
+    moves: Vec<Move>,
+    /// How many moves the plan may take.
+    budget: usize,
+    /// The fewest moves each arrangement has been reached in.
+    fewest: HashMap<Key, usize>,
+    /// Arrangements already searched, with the current budget when planning.
+    searched: HashSet<Key>,
+}
+
+impl Search {
+    fn new(elves: &[Elf], limit: u64) -> Self {
+        let items = elves
+            .iter()
+            .enumerate()
+            .map(|(i, elf)| {
+                let mut items: Vec<(u64, usize)> = elf.items().iter().map(|&c| (c, i)).collect();
+                items.sort_unstable_by(|a, b| b.cmp(a));
+                items
+            })
+            .collect();
+        let loads = elves.iter().map(|elf| elf.calories()).collect();
+
+        let mut search = Self {
+            limit,
+            items,
+            loads,
+            needed: vec![],
+            moves: vec![],
+            budget: 0,
+            fewest: HashMap::new(),
+            searched: HashSet::new(),
+        };
+        search.needed = (0..elves.len()).map(|elf| search.fewest_to_give(elf)).collect();
+        search
+    }
+
+    fn fewest_to_give(&self, elf: usize) -> usize {
+        let excess = self.loads[elf].saturating_sub(self.limit);
+        fewest_items(self.items[elf].iter().map(|&(calories, _)| calories), excess)
+    }
+
+    fn key(&self) -> Key {
+        let mut key: Key = self
+            .items
+            .iter()
+            .enumerate()
+            .flat_map(|(elf, items)| {
+                items
+                    .iter()
+                    .filter(move |&&(_, origin)| origin != elf)
+                    .map(move |&(calories, origin)| (origin, calories, elf))
+            })
+            .collect();
+        key.sort_unstable();
+        key
+    }
+
+    /// Whether any moves at all bring every elf within the limit.
+    fn reach(&mut self) -> bool {
+        if self.needed.iter().all(|&needed| needed == 0) {
+            return true;
+        }
+        if !self.searched.insert(self.key()) {
+            return false;
+        }
+
+        for (from, item, to) in self.next_moves() {
+            let at = self.shift(from, item, to);
+            if self.reach() {
+                return true;
+            }
+            self.unshift(from, item, to, at);
+        }
+        false
+    }
+
+    /// Find further moves that bring every elf within the limit, keeping within the budget.
+    /// An arrangement reached in more moves than before has already been searched further.
+    fn place(&mut self) -> bool {
+        let needed: usize = self.needed.iter().sum();
+        if needed == 0 {
+            return true;
+        }
+
+        let key = self.key();
+        let made = self.moves.len();
+        match self.fewest.get(&key) {
+            Some(&fewest) if fewest < made => return false,
+            Some(&fewest) if fewest == made && self.searched.contains(&key) => return false,
+            _ => {}
+        }
+        self.fewest.insert(key.clone(), made);
+        if made + needed > self.budget {
+            return false;
+        }
+        self.searched.insert(key);
+
+        for (from, item, to) in self.next_moves() {
+            let at = self.shift(from, item, to);
+            if self.place() {
+                return true;
+            }
+            self.unshift(from, item, to, at);
+        }
+        false
+    }
+
+    /// Every move that can be made next, as `(from, item, to)`. Elves over the limit give
+    /// first, as that is where the moves have to come from.
+    fn next_moves(&self) -> Vec<(usize, usize, usize)> {
+        let mut givers: Vec<usize> = (0..self.items.len()).collect();
+        givers.sort_by_key(|&elf| self.needed[elf] == 0);
+
+        let mut moves = vec![];
+        for from in givers {
+            for (item, &(calories, _)) in self.items[from].iter().enumerate() {
+                // Giving up one copy of an item instead of another makes no difference.
+                if item > 0 && self.items[from][item - 1].0 == calories {
+                    continue;
+                }
+                for to in 0..self.items.len() {
+                    if to != from && self.loads[to] + calories <= self.limit {
+                        moves.push((from, item, to));
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    /// Move an item from one elf to another, returning where it went in the other's items.
+    fn shift(&mut self, from: usize, item: usize, to: usize) -> usize {
+        let (calories, origin) = self.items[from].remove(item);
+        let at = self.items[to].partition_point(|&(other, _)| other > calories);
+        self.items[to].insert(at, (calories, origin));
+        self.loads[from] -= calories;
+        self.loads[to] += calories;
+        self.needed[from] = self.fewest_to_give(from);
+        self.moves.push(Move { from, to, calories });
+        at
+    }
+
+    fn unshift(&mut self, from: usize, item: usize, to: usize, at: usize) {
+        let (calories, origin) = self.items[to].remove(at);
+        self.items[from].insert(item, (calories, origin));
+        self.loads[from] += calories;
+        self.loads[to] -= calories;
+        self.needed[from] = self.fewest_to_give(from);
+        self.moves.pop();
+    }
+}
+
+/// How many of `items`, which are largest first and add up to at least `calories`, it takes
+/// to make up `calories`.
+fn fewest_items(items: impl Iterator<Item = u64>, calories: u64) -> usize {
+    let mut total = 0;
+    items
+        .take_while(|&item| {
+            let short = total < calories;
+            total += item;
+            short
+        })
+        .count()
+}
+
+/// Plan the transfers that leave the heaviest elf as light as possible, using the fewest moves
+/// for that load, under the same rules as [`plan_limit`].
+///
+/// Binary searches for the lowest limit that can be met. That is sound because meeting a limit
+/// means meeting every higher one too: the same moves still leave every elf taking an item
+/// within the higher limit.
+pub fn plan_balance(elves: &[Elf]) -> Result<Plan> {
+    if elves.is_empty() {
+        return Err(Error::Semantic("No elves on this trip!".to_string()));
+    }
+
+    let loads: Vec<u128> = elves.iter().map(|elf| elf.calories() as u128).collect();
+    let total: u128 = loads.iter().sum();
+    let largest_item = elves.iter().filter_map(|elf| elf.largest_item()).max().unwrap_or(0);
+
+    // No plan can do better than an even split, nor split an item, and doing nothing already
+    // meets the heaviest elf's load.
+    let mut low = (total.div_ceil(loads.len() as u128) as u64).max(largest_item);
+    let mut high = *loads.iter().max().unwrap() as u64;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if check_limit(elves, mid).is_ok() {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    plan_limit(elves, high)
+}
+
+/// Borrow two different elves mutably.
+fn pair_mut(elves: &mut [Elf], a: usize, b: usize) -> (&mut Elf, &mut Elf) {
+    if a < b {
+        let (left, right) = elves.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = elves.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_elves;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    fn loads(elves: &[Elf]) -> Vec<u64> {
+        elves.iter().map(|elf| elf.calories()).collect()
+    }
+
+    #[test]
+    fn test_plan_limit_moves_largest_items_off_overloaded_elves() {
+        let elves = parse_elves(EXAMPLE).unwrap();
+        let plan = plan_limit(&elves, 15000).unwrap();
+
+        assert_eq!(plan.moves, vec![Move { from: 3, to: 0, calories: 9000 }]);
+    }
+
+    #[test]
+    fn test_plan_limit_reaches_13000_in_two_moves() {
+        let mut elves = parse_elves(EXAMPLE).unwrap();
+        let plan = plan_limit(&elves, 13000).unwrap();
+
+        assert_eq!(
+            plan.moves,
+            vec![
+                Move { from: 3, to: 1, calories: 9000 },
+                Move { from: 3, to: 0, calories: 7000 },
+            ]
+        );
+        plan.apply(&mut elves).unwrap();
+        assert_eq!(loads(&elves), vec![13000, 13000, 11000, 8000, 10000]);
+    }
+
+    #[test]
+    fn test_plan_limit_reaches_12000_by_making_room() {
+        let mut elves = parse_elves(EXAMPLE).unwrap();
+        let plan = plan_limit(&elves, 12000).unwrap();
+
+        plan.apply(&mut elves).unwrap();
+
+        assert_eq!(plan.moves.len(), 3);
+        assert_eq!(loads(&elves).iter().all(|&load| load <= 12000), true);
+    }
+
+    #[test]
+    fn test_plan_limit_lets_a_light_elf_pass_items_on() {
+        let elves = parse_elves("5\n5\n\n1\n\n4\n").unwrap();
+        let plan = plan_limit(&elves, 5).unwrap();
+
+        assert_eq!(
+            plan.moves,
+            vec![Move { from: 1, to: 2, calories: 1 }, Move { from: 0, to: 1, calories: 5 }]
+        );
+    }
+
+    #[test]
+    fn test_plan_limit_11000_is_out_of_reach() {
+        // The items split evenly into 11000 each, but with every elf full there is nowhere to
+        // put an item down while making the swaps that would take.
+        let elves = parse_elves(EXAMPLE).unwrap();
+
+        assert_eq!(plan_limit(&elves, 11000).is_err(), true);
+    }
+
+    #[test]
+    fn test_plan_limit_backtracks_out_of_a_bad_placement() {
+        // The first elf must give up its 3 rather than its 6, which fits nowhere, and putting
+        // the 3 in the elf with the most room would leave none for the second elf's 4.
+        let elves = parse_elves("6\n3\n\n6\n4\n\n2\n\n3\n").unwrap();
+        let plan = plan_limit(&elves, 6).unwrap();
+
+        assert_eq!(
+            plan.moves,
+            vec![Move { from: 0, to: 3, calories: 3 }, Move { from: 1, to: 2, calories: 4 }]
+        );
+    }
+
+    #[test]
+    fn test_plan_limit_uses_fewest_moves() {
+        // Shedding the two 3s takes two moves, but the 6 alone is enough.
+        let elves = parse_elves("6\n3\n3\n\n0\n").unwrap();
+
+        assert_eq!(plan_limit(&elves, 6).unwrap().moves.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_limit_already_met_needs_no_moves() {
+        let elves = parse_elves(EXAMPLE).unwrap();
+
+        assert_eq!(plan_limit(&elves, 24000).unwrap().moves, vec![]);
+    }
+
+    #[test]
+    fn test_plan_limit_below_an_item_fails() {
+        let elves = parse_elves(EXAMPLE).unwrap();
+
+        assert_eq!(plan_limit(&elves, 9999).is_err(), true);
+    }
+
+    #[test]
+    fn test_plan_limit_without_room_fails() {
+        let elves = parse_elves("5\n5\n\n5\n").unwrap();
+
+        assert_eq!(plan_limit(&elves, 5).is_err(), true);
+    }
+
+    #[test]
+    fn test_plan_apply_meets_the_limit() {
+        let mut elves = parse_elves(EXAMPLE).unwrap();
+        let plan = plan_limit(&elves, 14000).unwrap();
+
+        plan.apply(&mut elves).unwrap();
+
+        assert_eq!(plan.moves.len(), 2);
+        assert_eq!(loads(&elves).iter().all(|&load| load <= 14000), true);
+        assert_eq!(loads(&elves).iter().sum::<u64>(), 55000);
+    }
+
+    #[test]
+    fn test_plan_balance_finds_lightest_heaviest_elf() {
+        let mut elves = parse_elves(EXAMPLE).unwrap();
+        let plan = plan_balance(&elves).unwrap();
+
+        plan.apply(&mut elves).unwrap();
+
+        assert_eq!(plan.limit, 12000);
+        assert_eq!(plan.moves.len(), 3);
+        assert_eq!(loads(&elves).into_iter().max(), Some(12000));
+    }
+
+    #[test]
+    fn test_plan_balance_of_even_elves_needs_no_moves() {
+        let elves = parse_elves("3\n\n1\n2\n").unwrap();
+
+        assert_eq!(plan_balance(&elves).unwrap(), Plan { limit: 3, moves: vec![] });
+    }
+
+    #[test]
+    fn test_move_display() {
+        let step = Move { from: 3, to: 1, calories: 9000 };
+
+        assert_eq!(step.to_string(), "Elf 4 gives 9000 calories to elf 2");
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+
+use common::{Error, Result};
+use serde::Serialize;
+
+use crate::Elf;
+
+/// The percentiles listed in a report, using the nearest-rank method.
+const PERCENTILES: [u8; 5] = [10, 25, 75, 90, 99];
+
+/// How many equal-width buckets the histogram splits the calorie range into.
+const BUCKETS: usize = 10;
+
+/// Characters in the histogram's longest bar; the others are scaled to match.
+const BAR_WIDTH: usize = 50;
+
+/// Elves whose calories fall in `start..=end`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Bucket {
+    pub start: u64,
+    pub end: u64,
+    pub count: usize,
+}
+
+/// Summary statistics of the calories carried by a group of elves. Elves are named by their
+/// number, counting from 1 in the order they appear in the input.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub elves: usize,
+    pub total: u128,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub percentiles: Vec<(u8, u64)>,
+    pub histogram: Vec<Bucket>,
+    pub min_items: usize,
+    pub max_items: usize,
+    pub mean_items: f64,
+    pub least_calories: Vec<usize>,
+    pub most_calories: Vec<usize>,
+}
+
+impl Report {
+    pub fn new(elves: &[Elf]) -> Result<Self> {
+        if elves.is_empty() {
+            return Err(Error::Semantic("No elves on this trip!".to_string()));
+        }
+
+        let mut calories: Vec<u64> = elves.iter().map(|elf| elf.calories()).collect();
+        calories.sort_unstable();
+
+        let count = calories.len();
+        let total: u128 = calories.iter().map(|&c| c as u128).sum();
+        let mean = total as f64 / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (calories[count / 2 - 1] as f64 + calories[count / 2] as f64) / 2.0
+        } else {
+            calories[count / 2] as f64
+        };
+        let variance = calories
+            .iter()
+            .map(|&c| (c as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        let min = calories[0];
+        let max = calories[count - 1];
+        let items = elves.iter().map(|elf| elf.items().len());
+
+        Ok(Self {
+            elves: count,
+            total,
+            min,
+            max,
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            percentiles: PERCENTILES
+                .iter()
+                .map(|&p| (p, nearest_rank(&calories, p)))
+                .collect(),
+            histogram: histogram(&calories),
+            min_items: items.clone().min().unwrap_or(0),
+            max_items: items.clone().max().unwrap_or(0),
+            mean_items: items.sum::<usize>() as f64 / count as f64,
+            least_calories: numbers_carrying(elves, min),
+            most_calories: numbers_carrying(elves, max),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a report is always valid JSON")
+    }
+}
+
+/// Renders the report as a human readable table.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers = |elves: &[usize]| -> String {
+            elves.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(", ")
+        };
+
+        writeln!(f, "Elves              | {}", self.elves)?;
+        writeln!(f, "Total calories     | {}", self.total)?;
+        writeln!(f, "Mean               | {:.2}", self.mean)?;
+        writeln!(f, "Median             | {:.1}", self.median)?;
+        writeln!(f, "Standard deviation | {:.2}", self.std_dev)?;
+        writeln!(f, "Least calories     | {} (elf {})", self.min, numbers(&self.least_calories))?;
+        writeln!(f, "Most calories      | {} (elf {})", self.max, numbers(&self.most_calories))?;
+        for (p, calories) in &self.percentiles {
+            writeln!(f, "{:<18} | {}", format!("{}th percentile", p), calories)?;
+        }
+        writeln!(
+            f,
+            "Items per elf      | {} to {}, {:.2} on average",
+            self.min_items, self.max_items, self.mean_items
+        )?;
+
+        let width = self.histogram.iter().map(|b| b.end.to_string().len()).max().unwrap_or(0);
+        let most = self.histogram.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+        writeln!(f, "\n{:<w$} | Elves", "Calories", w = 2 * width + 1)?;
+        for bucket in &self.histogram {
+            writeln!(
+                f,
+                "{:>w$}-{:<w$} | {:>4} {}",
+                bucket.start,
+                bucket.end,
+                bucket.count,
+                "#".repeat(bar(bucket.count, most)),
+                w = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The length of the bar for `count` elves when `most` gets the full width. Any elves at all
+/// get at least one character.
+fn bar(count: usize, most: usize) -> usize {
+    (count as u128 * BAR_WIDTH as u128).div_ceil(most as u128) as usize
+}
+
+/// The smallest value at or above `p` percent of the sorted values.
+fn nearest_rank(sorted: &[u64], p: u8) -> u64 {
+    let rank = (p as usize * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Split the range of the sorted values into equal-width buckets and count each.
+fn histogram(sorted: &[u64]) -> Vec<Bucket> {
+    // Work in u128 so the span of a histogram reaching u64::MAX still fits.
+    let min = sorted[0] as u128;
+    let max = sorted[sorted.len() - 1] as u128;
+    let width = (max - min + 1).div_ceil(BUCKETS as u128);
+
+    let mut buckets: Vec<Bucket> = (0..BUCKETS as u128)
+        .map(|i| min + i * width)
+        .take_while(|&start| start <= max)
+        .map(|start| Bucket {
+            start: start as u64,
+            end: (start + width - 1).min(max) as u64,
+            count: 0,
+        })
+        .collect();
+
+    for &calories in sorted {
+        buckets[((calories as u128 - min) / width) as usize].count += 1;
+    }
+    buckets
+}
+
+fn numbers_carrying(elves: &[Elf], calories: u64) -> Vec<usize> {
+    elves
+        .iter()
+        .filter(|elf| elf.calories() == calories)
+        .map(|elf| elf.id() + 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_elves;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    #[test]
+    fn test_report_summarises_example() {
+        let report = Report::new(&parse_elves(EXAMPLE).unwrap()).unwrap();
+
+        assert_eq!(report.elves, 5);
+        assert_eq!(report.total, 55000);
+        assert_eq!(report.mean, 11000.0);
+        assert_eq!(report.median, 10000.0);
+        assert_eq!(format!("{:.2}", report.std_dev), "6985.70");
+        assert_eq!(report.least_calories, vec![2]);
+        assert_eq!(report.most_calories, vec![4]);
+        assert_eq!((report.min_items, report.max_items), (1, 3));
+        assert_eq!(report.percentiles[0], (10, 4000));
+        assert_eq!(report.percentiles[4], (99, 24000));
+    }
+
+    #[test]
+    fn test_report_median_of_even_count_averages_middle_pair() {
+        let report = Report::new(&parse_elves("1\n\n2\n\n3\n\n10").unwrap()).unwrap();
+
+        assert_eq!(report.median, 2.5);
+    }
+
+    #[test]
+    fn test_report_lists_every_elf_at_an_extreme() {
+        let report = Report::new(&parse_elves("5\n\n1\n\n5\n\n1").unwrap()).unwrap();
+
+        assert_eq!(report.least_calories, vec![2, 4]);
+        assert_eq!(report.most_calories, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_report_of_no_elves_fails() {
+        assert_eq!(Report::new(&[]).is_err(), true);
+    }
+
+    #[test]
+    fn test_histogram_counts_every_elf() {
+        let buckets = histogram(&[0, 5, 9, 10, 19]);
+
+        assert_eq!(buckets.len(), 10);
+        assert_eq!(buckets[0], Bucket { start: 0, end: 1, count: 1 });
+        assert_eq!(buckets[9], Bucket { start: 18, end: 19, count: 1 });
+        assert_eq!(buckets.iter().map(|b| b.count).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_histogram_of_equal_elves_has_one_bucket() {
+        assert_eq!(histogram(&[7, 7]), vec![Bucket { start: 7, end: 7, count: 2 }]);
+    }
+
+    #[test]
+    fn test_histogram_spanning_every_calorie_count() {
+        let buckets = histogram(&[0, u64::MAX]);
+
+        assert_eq!(buckets.len(), 10);
+        assert_eq!(buckets[9].end, u64::MAX);
+        assert_eq!(buckets[9].count, 1);
+    }
+
+    #[test]
+    fn test_histogram_bars_are_scaled() {
+        assert_eq!(bar(2_000_000, 2_000_000), BAR_WIDTH);
+        assert_eq!(bar(1_000_000, 2_000_000), BAR_WIDTH / 2);
+        assert_eq!(bar(1, 2_000_000), 1);
+        assert_eq!(bar(0, 2_000_000), 0);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let report = Report::new(&parse_elves("1\n2\n\n3").unwrap()).unwrap();
+
+        assert_eq!(
+            report.to_json(),
+            "{\"elves\":2,\"total\":6,\"min\":3,\"max\":3,\"mean\":3.0,\"median\":3.0,\
+             \"std_dev\":0.0,\"percentiles\":[[10,3],[25,3],[75,3],[90,3],[99,3]],\
+             \"histogram\":[{\"start\":3,\"end\":3,\"count\":2}],\
+             \"min_items\":1,\"max_items\":2,\"mean_items\":1.5,\
+             \"least_calories\":[1,2],\"most_calories\":[1,2]}"
+        );
+    }
+}
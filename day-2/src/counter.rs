@@ -0,0 +1,278 @@
+use common::{Error, Result};
+
+use crate::{calculate_result, Outcome, Play, Scoring};
+
+/// Limits on the plays a counter-strategy may use.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Constraints {
+    /// Draw at most this many rounds.
+    pub max_draws: Option<usize>,
+    /// Play the same shape at most this many rounds in a row.
+    pub max_run: Option<usize>,
+}
+
+/// The plays that score the most against a known sequence of opponent plays.
+#[derive(Debug, PartialEq)]
+pub struct Counter {
+    pub plays: Vec<Play>,
+    pub score: i64,
+}
+
+/// Find the highest scoring plays against `opponents` that keep within `constraints`.
+///
+/// Works back from the last round, keeping the best score still to come for every state a
+/// round can start in: the draws used so far, the shape last played and how many times in a
+/// row. Draws and runs are only tracked when they are constrained, and limits of at least the
+/// number of rounds can never bind so count as no limit. Ties go to rock, then paper, then
+/// scissors.
+///
+/// With `n` rounds, `d` allowed draws and runs of up to `r`, there are `3 (d + 1) r` states
+/// per round. Time is O(n d r), and memory is dominated by the choice made in every state of
+/// every round, kept at two bits each: `3 n (d + 1) r / 4` bytes.
+pub fn best_counter(
+    opponents: &[Play],
+    constraints: &Constraints,
+    scoring: &Scoring,
+) -> Result<Counter> {
+    if constraints.max_run == Some(0) {
+        return Err(Error::Semantic(
+            "Every round needs a play, so runs cannot be capped at 0".to_string(),
+        ));
+    }
+
+    let n = opponents.len();
+    if n == 0 {
+        return Ok(Counter { plays: vec![], score: 0 });
+    }
+
+    let draw_cap = constraints.max_draws.filter(|&k| k < n);
+    let run_cap = constraints.max_run.filter(|&m| m < n);
+    let draw_states = draw_cap.map_or(1, |k| k + 1);
+    let run_states = run_cap.unwrap_or(1);
+    let states = draw_states * 3 * run_states;
+    let index = |s: State| (s.draws * 3 + s.last.index()) * run_states + (s.run - 1);
+
+    // What starting a round in `state` and playing `play` against `opponent` leads to.
+    let step = |state: Option<State>, play: Play, opponent: Play| -> Option<State> {
+        let draws = state.map_or(0, |s| s.draws);
+        let draws = match draw_cap {
+            Some(cap) if calculate_result(&play, &opponent) == Outcome::Draw => {
+                (draws < cap).then_some(draws + 1)?
+            }
+            _ => draws,
+        };
+        let run = match (state, run_cap) {
+            (Some(s), Some(_)) if s.last == play => (s.run < run_states).then_some(s.run + 1)?,
+            _ => 1,
+        };
+        Some(State { draws, last: play, run })
+    };
+
+    const UNREACHABLE: i64 = i64::MIN;
+    let mut later = vec![0; states];
+    let mut choices = Choices::new(n * states);
+
+    for round in (1..n).rev() {
+        let opponent = opponents[round];
+        let mut best = vec![UNREACHABLE; states];
+        for state in State::all(draw_states, run_states) {
+            for play in Play::ALL {
+                let Some(next) = step(Some(state), play, opponent) else {
+                    continue;
+                };
+                if later[index(next)] == UNREACHABLE {
+                    continue;
+                }
+                let total = later[index(next)] + scoring.score_match(play, opponent);
+                if total > best[index(state)] {
+                    best[index(state)] = total;
+                    choices.set(round * states + index(state), play);
+                }
+            }
+        }
+        later = best;
+    }
+
+    let (score, first) = Play::ALL
+        .iter()
+        .filter_map(|&play| {
+            let next = step(None, play, opponents[0])?;
+            let rest = later[index(next)];
+            (rest != UNREACHABLE).then(|| (rest + scoring.score_match(play, opponents[0]), play))
+        })
+        .fold(None, |best: Option<(i64, Play)>, (score, play)| match best {
+            Some((top, _)) if top >= score => best,
+            _ => Some((score, play)),
+        })
+        .ok_or_else(|| Error::Semantic("No plays meet the constraints".to_string()))?;
+
+    let mut plays = vec![first];
+    let mut state = step(None, first, opponents[0]).unwrap();
+    for (round, &opponent) in opponents.iter().enumerate().skip(1) {
+        let play = choices.get(round * states + index(state));
+        plays.push(play);
+        state = step(Some(state), play, opponent).unwrap();
+    }
+
+    Ok(Counter { plays, score })
+}
+
+/// A play for every state of every round, packed four to a byte.
+struct Choices(Vec<u8>);
+
+impl Choices {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(4)])
+    }
+
+    fn get(&self, i: usize) -> Play {
+        Play::from_index((self.0[i / 4] >> (i % 4 * 2) & 0b11) as usize)
+    }
+
+    fn set(&mut self, i: usize, play: Play) {
+        let shift = i % 4 * 2;
+        self.0[i / 4] = self.0[i / 4] & !(0b11 << shift) | (play.index() as u8) << shift;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    draws: usize,
+    last: Play,
+    run: usize,
+}
+
+impl State {
+    fn all(draw_states: usize, run_states: usize) -> impl Iterator<Item = State> {
+        (0..draw_states).flat_map(move |draws| {
+            Play::ALL.into_iter().flat_map(move |last| {
+                (1..=run_states).map(move |run| State { draws, last, run })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    use Play::{Paper, Rock, Scissor};
+
+    fn score(plays: &[Play], opponents: &[Play]) -> i64 {
+        plays
+            .iter()
+            .zip(opponents)
+            .map(|(&you, &them)| Scoring::default().score_match(you, them))
+            .sum()
+    }
+
+    #[test]
+    fn test_unconstrained_counter_wins_every_round() {
+        let opponents = [Rock, Paper, Scissor];
+        let counter =
+            best_counter(&opponents, &Constraints::default(), &Scoring::default()).unwrap();
+
+        assert_eq!(counter.plays, vec![Paper, Scissor, Rock]);
+        assert_eq!(counter.score, 8 + 9 + 7);
+    }
+
+    #[test]
+    fn test_max_run_breaks_up_repeats() {
+        let opponents = [Rock, Rock, Rock];
+        let constraints = Constraints { max_run: Some(2), ..Constraints::default() };
+        let counter = best_counter(&opponents, &constraints, &Scoring::default()).unwrap();
+
+        // Paper can only win two of the rounds, and drawing with rock (1 + 3) beats losing with
+        // scissors (3 + 0) in the other. Rock goes first as ties favour it.
+        assert_eq!(counter.plays, vec![Rock, Paper, Paper]);
+        assert_eq!(counter.score, score(&counter.plays, &opponents));
+        assert_eq!(counter.score, 8 + 8 + 4);
+    }
+
+    #[test]
+    fn test_max_draws_limits_draws() {
+        // With these points a draw with scissors (10 + 3) beats a win with rock (0 + 6).
+        let scoring = Scoring { played: vec![0, 0, 10], ..Scoring::default() };
+        let opponents = [Scissor, Scissor];
+        let constraints = Constraints { max_draws: Some(1), ..Constraints::default() };
+
+        let counter = best_counter(&opponents, &constraints, &scoring).unwrap();
+
+        assert_eq!(counter.plays, vec![Rock, Scissor]);
+        assert_eq!(counter.score, 13 + 6);
+    }
+
+    #[test]
+    fn test_counter_matches_brute_force() {
+        let opponents = [Rock, Scissor, Scissor, Paper, Rock, Rock];
+        let constraints = Constraints { max_draws: Some(1), max_run: Some(1) };
+        let counter = best_counter(&opponents, &constraints, &Scoring::default()).unwrap();
+
+        let mut best = i64::MIN;
+        for code in 0..3usize.pow(opponents.len() as u32) {
+            let plays: Vec<Play> = (0..opponents.len())
+                .map(|i| Play::from_index(code / 3usize.pow(i as u32) % 3))
+                .collect();
+            let draws = plays
+                .iter()
+                .zip(&opponents)
+                .filter(|(you, them)| calculate_result(you, them) == Outcome::Draw)
+                .count();
+            if draws <= 1 && plays.windows(2).all(|pair| pair[0] != pair[1]) {
+                best = best.max(score(&plays, &opponents));
+            }
+        }
+
+        assert_eq!(counter.score, best);
+        assert_eq!(score(&counter.plays, &opponents), best);
+    }
+
+    #[test]
+    fn test_counter_scores_past_i32() {
+        let scoring = Scoring { win: i32::MAX, ..Scoring::default() };
+        let counter =
+            best_counter(&[Rock, Rock, Rock], &Constraints::default(), &scoring).unwrap();
+
+        assert_eq!(counter.score, 3 * (i32::MAX as i64 + 2));
+    }
+
+    #[test]
+    fn test_limits_that_cannot_bind_are_ignored() {
+        let opponents = [Rock, Paper, Scissor, Rock];
+        let constraints = Constraints { max_draws: Some(4), max_run: Some(usize::MAX) };
+
+        assert_eq!(
+            best_counter(&opponents, &constraints, &Scoring::default()).unwrap(),
+            best_counter(&opponents, &Constraints::default(), &Scoring::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_choices_pack_four_plays_a_byte() {
+        let mut choices = Choices::new(6);
+        for (i, play) in [Scissor, Paper, Rock, Scissor, Paper].into_iter().enumerate() {
+            choices.set(i, play);
+        }
+        choices.set(1, Rock);
+
+        assert_eq!(choices.0.len(), 2);
+        let plays: Vec<Play> = (0..6).map(|i| choices.get(i)).collect();
+        assert_eq!(plays, vec![Scissor, Rock, Rock, Scissor, Paper, Rock]);
+    }
+
+    #[test]
+    fn test_zero_max_run_is_rejected() {
+        let constraints = Constraints { max_run: Some(0), ..Constraints::default() };
+
+        assert_eq!(best_counter(&[Rock], &constraints, &Scoring::default()).is_err(), true);
+    }
+
+    #[test]
+    fn test_empty_guide() {
+        let counter = best_counter(&[], &Constraints::default(), &Scoring::default()).unwrap();
+
+        assert_eq!(counter, Counter { plays: vec![], score: 0 });
+    }
+}
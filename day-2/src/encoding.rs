@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::{Outcome, Play, Round, Scoring, Strategy};
+
+/// The six orders of three things, as indexes.
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// One way the letters `X`, `Y` and `Z` could be read: as three different plays, or as three
+/// different outcomes. Entries are in `X`, `Y`, `Z` order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mapping {
+    Plays([Play; 3]),
+    Outcomes([Outcome; 3]),
+}
+
+impl Mapping {
+    /// Every mapping of the letters to plays, then every mapping to outcomes.
+    pub fn all() -> Vec<Mapping> {
+        let plays = PERMUTATIONS.map(|order| Mapping::Plays(order.map(Play::from_index)));
+        let outcomes = PERMUTATIONS.map(|order| Mapping::Outcomes(order.map(|i| Outcome::ALL[i])));
+        plays.into_iter().chain(outcomes).collect()
+    }
+
+    /// What to play against `opponent` when the second column is `response`.
+    pub fn to_play(&self, opponent: Play, response: usize) -> Play {
+        match self {
+            Mapping::Plays(plays) => plays[response],
+            Mapping::Outcomes(outcomes) => Strategy::what_to_play(outcomes[response], opponent),
+        }
+    }
+
+    pub fn score(&self, rounds: &[Round], scoring: &Scoring) -> i64 {
+        score_counts(self, &count_rounds(rounds), scoring)
+    }
+}
+
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mapping::Plays([x, y, z]) => write!(f, "as play: X = {:?}, Y = {:?}, Z = {:?}", x, y, z),
+            Mapping::Outcomes([x, y, z]) => {
+                write!(f, "as outcome: X = {:?}, Y = {:?}, Z = {:?}", x, y, z)
+            }
+        }
+    }
+}
+
+/// How many times each opponent play and response pair appears in the guide.
+fn count_rounds(rounds: &[Round]) -> [[usize; 3]; 3] {
+    let mut counts = [[0; 3]; 3];
+    for round in rounds {
+        counts[round.opponent.index()][round.response.index()] += 1;
+    }
+    counts
+}
+
+fn score_counts(mapping: &Mapping, counts: &[[usize; 3]; 3], scoring: &Scoring) -> i64 {
+    let mut total = 0;
+    for opponent in Play::ALL {
+        for (response, &count) in counts[opponent.index()].iter().enumerate() {
+            let play = mapping.to_play(opponent, response);
+            total += count as i64 * scoring.score_match(play, opponent);
+        }
+    }
+    total
+}
+
+/// Every mapping of the second column under which following the guide scores `target`.
+///
+/// The guide is reduced to a count of each of the nine kinds of line first, so trying all
+/// twelve mappings costs no more than reading the guide once.
+pub fn find_mappings(rounds: &[Round], target: i64, scoring: &Scoring) -> Vec<Mapping> {
+    let counts = count_rounds(rounds);
+    Mapping::all()
+        .into_iter()
+        .filter(|mapping| score_counts(mapping, &counts, scoring) == target)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_guide, total_score, Decoder};
+
+    const EXAMPLE: &str = "A Y\nB X\nC Z\n";
+
+    #[test]
+    fn test_all_mappings_are_distinct() {
+        let mappings = Mapping::all();
+
+        assert_eq!(mappings.len(), 12);
+        for (i, mapping) in mappings.iter().enumerate() {
+            assert_eq!(mappings[..i].contains(mapping), false);
+        }
+    }
+
+    #[test]
+    fn test_puzzle_mappings_match_the_decoders() {
+        let rounds = parse_guide(EXAMPLE).unwrap();
+        let scoring = Scoring::default();
+        let as_play = Mapping::Plays([Play::Rock, Play::Paper, Play::Scissor]);
+        let as_outcome = Mapping::Outcomes([Outcome::Loss, Outcome::Draw, Outcome::Win]);
+
+        assert_eq!(as_play.score(&rounds, &scoring), total_score(&rounds, Decoder::Play, &scoring));
+        assert_eq!(
+            as_outcome.score(&rounds, &scoring),
+            total_score(&rounds, Decoder::Outcome, &scoring)
+        );
+    }
+
+    #[test]
+    fn test_find_mappings_singles_out_the_outcome_reading() {
+        let rounds = parse_guide(EXAMPLE).unwrap();
+
+        assert_eq!(
+            find_mappings(&rounds, 12, &Scoring::default()),
+            vec![Mapping::Outcomes([Outcome::Loss, Outcome::Draw, Outcome::Win])]
+        );
+    }
+
+    #[test]
+    fn test_find_mappings_reports_every_match() {
+        let rounds = parse_guide(EXAMPLE).unwrap();
+
+        // Three rounds leave plenty of ways to score 15, the puzzle's play reading among them.
+        let found = find_mappings(&rounds, 15, &Scoring::default());
+
+        assert_eq!(found.len(), 8);
+        assert_eq!(found.contains(&Mapping::Plays([Play::Rock, Play::Paper, Play::Scissor])), true);
+        for mapping in &found {
+            assert_eq!(mapping.score(&rounds, &Scoring::default()), 15);
+        }
+    }
+
+    #[test]
+    fn test_find_mappings_without_a_match() {
+        let rounds = parse_guide(EXAMPLE).unwrap();
+
+        assert_eq!(find_mappings(&rounds, 1000, &Scoring::default()), vec![]);
+    }
+
+    #[test]
+    fn test_mapping_display() {
+        let mapping = Mapping::Outcomes([Outcome::Loss, Outcome::Draw, Outcome::Win]);
+
+        assert_eq!(mapping.to_string(), "as outcome: X = Loss, Y = Draw, Z = Win");
+    }
+}
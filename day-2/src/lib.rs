@@ -0,0 +1,465 @@
+use std::fmt;
+use std::str::FromStr;
+
+use common::{tokens, Error, Solution};
+use serde::Serialize;
+
+mod counter;
+mod encoding;
+mod report;
+mod rng;
+mod rules;
+mod scoring;
+mod tournament;
+
+pub use counter::{best_counter, Constraints, Counter};
+pub use encoding::{find_mappings, Mapping};
+pub use report::{breakdown, to_csv, to_json, Breakdown, RoundReport, Summary};
+pub use rng::Rng;
+pub use rules::Rules;
+pub use scoring::Scoring;
+pub use tournament::{
+    round_robin, FrequencyCounter, Guide, LastMoveBeater, Markov, Player, Random, Standing, Table,
+};
+
+/// A shape of rock, paper, scissors, numbered as its move in [`Rules::classic`]. Other games
+/// are played by move index, through [`Rules`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Play {
+    Rock = 0,
+    Paper = 1,
+    #[serde(rename = "scissors")]
+    Scissor = 2,
+}
+
+impl Play {
+    pub const ALL: [Play; 3] = [Play::Rock, Play::Paper, Play::Scissor];
+
+    /// Where the play sits in [`Rules::classic`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_index(index: usize) -> Play {
+        Play::ALL[index]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Play::Rock => "rock",
+            Play::Paper => "paper",
+            Play::Scissor => "scissors",
+        }
+    }
+}
+
+impl FromStr for Play {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Play, Self::Err> {
+        match input {
+            // Opponent
+            "A" => Ok(Play::Rock),
+            "B" => Ok(Play::Paper),
+            "C" => Ok(Play::Scissor),
+            _ => Err("expected an opponent play of A, B or C".to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl Outcome {
+    /// In the order `X`, `Y` and `Z` stand for them in the puzzle.
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+    pub fn index(self) -> usize {
+        Outcome::ALL.iter().position(|&outcome| outcome == self).unwrap()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Outcome::Loss => "loss",
+            Outcome::Draw => "draw",
+            Outcome::Win => "win",
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Outcome, Self::Err> {
+        match input {
+            // Opponent
+            "X" => Ok(Outcome::Loss),
+            "Y" => Ok(Outcome::Draw),
+            "Z" => Ok(Outcome::Win),
+            _ => Err("expected an outcome of X, Y or Z".to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Strategy {
+    pub desired_outcome: Outcome,
+    pub opponent: Play,
+    pub to_play: Play,
+}
+
+impl Strategy {
+    pub fn new(desired_outcome: Outcome, opponent: Play) -> Self {
+        Self {
+            desired_outcome,
+            opponent,
+            to_play: self::Strategy::what_to_play(desired_outcome, opponent),
+        }
+    }
+
+    /// Return the Play required to achieve the desired result
+    pub fn what_to_play(desired_outcome: Outcome, opponent: Play) -> Play {
+        // Every outcome can be reached against every move of the classic game.
+        let play = Rules::classic().what_to_play(desired_outcome, opponent.index());
+        Play::from_index(play.unwrap())
+    }
+}
+
+pub fn calculate_result(you: &Play, opponent: &Play) -> Outcome {
+    Rules::classic().outcome(you.index(), opponent.index())
+}
+
+pub fn score_for_played(played: &Play) -> i64 {
+    Scoring::default().played(*played)
+}
+
+pub fn score_for_outcome(result: Outcome) -> i64 {
+    Scoring::default().outcome(result)
+}
+
+pub fn score_for_match(you: Play, opponent: Play) -> i64 {
+    Scoring::default().score_match(you, opponent)
+}
+
+/// The second column of the strategy guide, whose meaning depends on how it is decoded.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Response {
+    X,
+    Y,
+    Z,
+}
+
+impl Response {
+    /// 0, 1 or 2 for `X`, `Y` or `Z`.
+    pub fn index(self) -> usize {
+        match self {
+            Response::X => 0,
+            Response::Y => 1,
+            Response::Z => 2,
+        }
+    }
+}
+
+impl FromStr for Response {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Response, Self::Err> {
+        match input {
+            "X" => Ok(Response::X),
+            "Y" => Ok(Response::Y),
+            "Z" => Ok(Response::Z),
+            _ => Err("expected a response of X, Y or Z".to_string()),
+        }
+    }
+}
+
+/// A line of the strategy guide, as written.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Round {
+    pub opponent: Play,
+    pub response: Response,
+}
+
+impl Round {
+    /// The line that, read with [`Decoder::Play`], plays `play` against `opponent`.
+    pub fn for_play(opponent: Play, play: Play) -> Round {
+        let response = [Response::X, Response::Y, Response::Z][play.index()];
+        Round { opponent, response }
+    }
+}
+
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opponent = ["A", "B", "C"][self.opponent.index()];
+        write!(f, "{} {:?}", opponent, self.response)
+    }
+}
+
+/// The ways of reading the second column of the guide.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Decoder {
+    /// `X`, `Y` and `Z` are rock, paper and scissors to play.
+    Play,
+    /// `X`, `Y` and `Z` are the round to lose, draw or win.
+    Outcome,
+}
+
+impl Decoder {
+    pub const ALL: [Decoder; 2] = [Decoder::Play, Decoder::Outcome];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Decoder::Play => "play",
+            Decoder::Outcome => "outcome",
+        }
+    }
+
+    pub fn decode(self, round: &Round) -> Strategy {
+        let index = round.response.index();
+
+        match self {
+            Decoder::Play => {
+                let to_play = Play::from_index(index);
+                Strategy {
+                    desired_outcome: calculate_result(&to_play, &round.opponent),
+                    opponent: round.opponent,
+                    to_play,
+                }
+            }
+            Decoder::Outcome => {
+                Strategy::new(Outcome::ALL[index], round.opponent)
+            }
+        }
+    }
+}
+
+impl FromStr for Decoder {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Decoder, Self::Err> {
+        Decoder::ALL
+            .into_iter()
+            .find(|decoder| decoder.name() == input)
+            .ok_or_else(|| format!("expected a decoder of play or outcome, found '{}'", input))
+    }
+}
+
+/// Total score of following the guide, reading it with the given decoder.
+pub fn total_score(rounds: &[Round], decoder: Decoder, scoring: &Scoring) -> i64 {
+    rounds
+        .iter()
+        .map(|round| decoder.decode(round))
+        .map(|strat| scoring.score_match(strat.to_play, strat.opponent))
+        .sum()
+}
+
+/// Parse a single `<opponent> <response>` line of the strategy guide.
+pub fn parse_round(line: &str) -> common::Result<Round> {
+    let mut columns = tokens(line);
+    let end = line.len() + 1;
+
+    let (column, text) = columns
+        .next()
+        .ok_or_else(|| Error::parse(1, end, "", "missing opponent play"))?;
+    let opponent = Play::from_str(text).map_err(|e| Error::parse(1, column, text, e))?;
+
+    let (column, text) = columns
+        .next()
+        .ok_or_else(|| Error::parse(1, end, "", "missing response"))?;
+    let response = Response::from_str(text).map_err(|e| Error::parse(1, column, text, e))?;
+
+    if let Some((column, text)) = columns.next() {
+        return Err(Error::parse(1, column, text, "unexpected text after response"));
+    }
+
+    Ok(Round { opponent, response })
+}
+
+pub fn parse_guide(input: &str) -> common::Result<Vec<Round>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_round(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Round>;
+    type Answer = i64;
+
+    fn parse(input: &str) -> common::Result<Self::Input> {
+        parse_guide(input)
+    }
+
+    /// Total score when the second column is the shape to play.
+    fn part1(rounds: &Self::Input) -> common::Result<Self::Answer> {
+        Ok(total_score(rounds, Decoder::Play, &Scoring::default()))
+    }
+
+    /// Total score when the second column is the desired outcome of each round.
+    fn part2(rounds: &Self::Input) -> common::Result<Self::Answer> {
+        Ok(total_score(rounds, Decoder::Outcome, &Scoring::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_basic_game_rules() {
+        //Rock
+        assert_eq!(calculate_result(&Play::Rock, &Play::Scissor), Outcome::Win);
+        assert_eq!(calculate_result(&Play::Rock, &Play::Rock), Outcome::Draw);
+        assert_eq!(calculate_result(&Play::Rock, &Play::Paper), Outcome::Loss);
+
+        //Paper
+        assert_eq!(calculate_result(&Play::Paper, &Play::Rock), Outcome::Win);
+        assert_eq!(calculate_result(&Play::Paper, &Play::Paper), Outcome::Draw);
+        assert_eq!(
+            calculate_result(&Play::Paper, &Play::Scissor),
+            Outcome::Loss
+        );
+
+        //Scissor
+        assert_eq!(calculate_result(&Play::Scissor, &Play::Paper), Outcome::Win);
+        assert_eq!(
+            calculate_result(&Play::Scissor, &Play::Scissor),
+            Outcome::Draw
+        );
+        assert_eq!(calculate_result(&Play::Scissor, &Play::Rock), Outcome::Loss);
+    }
+
+    #[test]
+    fn test_match_score() {
+        //Rock (1 point + outcome)
+        assert_eq!(score_for_match(Play::Rock, Play::Scissor), 1 + 6);
+        assert_eq!(score_for_match(Play::Rock, Play::Rock), 1 + 3);
+        assert_eq!(score_for_match(Play::Rock, Play::Paper), 1);
+
+        //Paper (2 points + outcome)
+        assert_eq!(score_for_match(Play::Paper, Play::Rock), 2 + 6);
+        assert_eq!(score_for_match(Play::Paper, Play::Paper), 2 + 3);
+        assert_eq!(score_for_match(Play::Paper, Play::Scissor), 2);
+
+        //Scissor (3 points + outcome)
+        assert_eq!(score_for_match(Play::Scissor, Play::Paper), 3 + 6);
+        assert_eq!(score_for_match(Play::Scissor, Play::Scissor), 3 + 3);
+        assert_eq!(score_for_match(Play::Scissor, Play::Rock), 3);
+    }
+
+    #[test]
+    fn test_to_play_calculations() {
+        // To Win
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Win, Play::Rock),
+            Play::Paper
+        );
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Win, Play::Paper),
+            Play::Scissor
+        );
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Win, Play::Scissor),
+            Play::Rock
+        );
+
+        // To Draw
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Draw, Play::Rock),
+            Play::Rock
+        );
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Draw, Play::Paper),
+            Play::Paper
+        );
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Draw, Play::Scissor),
+            Play::Scissor
+        );
+
+        // To Loose
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Loss, Play::Rock),
+            Play::Scissor
+        );
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Loss, Play::Paper),
+            Play::Rock
+        );
+        assert_eq!(
+            Strategy::what_to_play(Outcome::Loss, Play::Scissor),
+            Play::Paper
+        );
+    }
+
+    #[test]
+    fn test_parse_round_reports_bad_column() {
+        assert_eq!(
+            parse_guide("A Y\nB Q\n").err().unwrap().to_string(),
+            "line 2, column 3: expected a response of X, Y or Z (found 'Q')"
+        );
+    }
+
+    #[test]
+    fn test_parse_round_reports_missing_column() {
+        assert_eq!(
+            parse_round("C").err().unwrap().to_string(),
+            "line 1, column 2: missing response (found '')"
+        );
+    }
+
+    #[test]
+    fn test_example_with_both_decoders() {
+        let rounds = Day2::parse("A Y\nB X\nC Z\n").unwrap();
+
+        assert_eq!(Day2::part1(&rounds).unwrap(), 15);
+        assert_eq!(Day2::part2(&rounds).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_total_score_past_i32() {
+        let rounds = Day2::parse("A Y\nB X\nC Z\n").unwrap();
+        let scoring = Scoring::parse("win = 2000000000", Rules::classic()).unwrap();
+
+        assert_eq!(total_score(&rounds, Decoder::Outcome, &scoring), 2_000_000_000 + 1 + 4 + 1);
+        assert_eq!(total_score(&rounds, Decoder::Play, &scoring), 2_000_000_000 + 2 + 1 + 6);
+    }
+
+    #[test]
+    fn test_decoders_read_the_same_round_differently() {
+        let round = parse_round("A Y").unwrap();
+
+        let as_play = Decoder::Play.decode(&round);
+        assert_eq!((as_play.to_play, as_play.desired_outcome), (Play::Paper, Outcome::Win));
+
+        let as_outcome = Decoder::Outcome.decode(&round);
+        assert_eq!((as_outcome.to_play, as_outcome.desired_outcome), (Play::Rock, Outcome::Draw));
+    }
+
+    #[test]
+    fn test_decoder_from_name() {
+        assert_eq!("play".parse::<Decoder>(), Ok(Decoder::Play));
+        assert_eq!("outcome".parse::<Decoder>(), Ok(Decoder::Outcome));
+        assert_eq!("shape".parse::<Decoder>().is_err(), true);
+    }
+
+    #[test]
+    fn test_round_for_play_round_trips() {
+        let round = Round::for_play(Play::Scissor, Play::Rock);
+
+        assert_eq!(round.to_string(), "C X");
+        assert_eq!(parse_round(&round.to_string()).unwrap(), round);
+        assert_eq!(Decoder::Play.decode(&round).to_play, Play::Rock);
+    }
+}
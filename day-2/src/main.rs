@@ -1,177 +1,305 @@
-use std::{env, io};
-use std::fs::File;
-use std::io::BufRead;
-use std::path::Path;
-use std::str::FromStr;
-
-use common::read_lines;
-
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum Play {
-    Rock,
-    Paper,
-    Scissor,
+use std::env;
+use std::fs;
+use std::process::exit;
+
+use common::{Error, Solution};
+use day_2::{
+    best_counter, breakdown, find_mappings, round_robin, to_csv, to_json, total_score, Breakdown,
+    Constraints, Day2, Decoder, FrequencyCounter, Guide, LastMoveBeater, Markov, Player, Random,
+    Round, Rules, Scoring, Table,
+};
+
+const USAGE: &str = "\
+Usage: day-2 [--decoder <play|outcome>] [--scoring <file>] [--rules <file>]
+             [--tournament [--rounds <n>] [--seed <n>]]
+             [--counter [--max-draws <n>] [--max-run <n>]] [--target-score <n>]
+             [--report [--format <table|csv|json>]] <input>
+
+Prints the total score of following the strategy guide, reading its second column both as
+the shape to play and as the outcome to aim for, or only the way given with --decoder.
+
+  --scoring <file>  read the points for each shape and outcome from key = value lines, for
+                    example `win = 10`, keeping the puzzle's points for any left out
+  --rules <file>    play another game than rock, paper, scissors, read from a `moves = ...`
+                    line and `<move> beats <move>, ...` lines, or a `cyclic = ...` line for a
+                    balanced game such as `cyclic = rock, spock, paper, lizard, scissors`; the
+                    guide names the opponent's move by name or by letter from A, and its
+                    second column is read as the outcome
+  --tournament      pit the guide's shapes, read as plays unless --decoder says otherwise,
+                    against random, frequency-counting, last-move-beating and Markov players
+  --rounds <n>      rounds each pair of players plays, 1000 by default
+  --seed <n>        seed for the tournament's random choices, 2022 by default
+  --counter         print the highest scoring guide against the opponent column
+  --max-draws <n>   let the counter guide draw at most n rounds
+  --max-run <n>     let the counter guide play the same shape at most n rounds in a row
+  --target-score <n>
+                    list every reading of X, Y and Z, as plays or as outcomes, under which
+                    the guide scores n, failing if there are none
+  --report          list every round with its outcome, play, score and running total, then
+                    how often each play won, drew and lost; the guide is read as outcomes
+                    unless --decoder says otherwise
+  --format <f>      table (default), csv for the rounds alone, or json for both";
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Table,
+    Csv,
+    Json,
 }
 
-impl FromStr for Play {
-    type Err = String;
-
-    fn from_str(input: &str) -> Result<Play, Self::Err> {
-        match input {
-            // Opponent
-            "A" => Ok(Play::Rock),
-            "B" => Ok(Play::Paper),
-            "C" => Ok(Play::Scissor),
-            _ => Err("Unable to Match".parse().unwrap()),
-        }
-    }
+#[derive(Debug, PartialEq)]
+struct Tournament {
+    rounds: usize,
+    seed: u64,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum Outcome {
-    Win,
-    Loss,
-    Draw,
+struct Options {
+    decoder: Option<Decoder>,
+    scoring: Option<String>,
+    rules: Option<String>,
+    tournament: Option<Tournament>,
+    counter: Option<Constraints>,
+    target_score: Option<i64>,
+    report: Option<Format>,
+    input: String,
 }
 
-impl FromStr for Outcome {
-    type Err = String;
-
-    fn from_str(input: &str) -> Result<Outcome, Self::Err> {
-        match input {
-            // Opponent
-            "X" => Ok(Outcome::Loss),
-            "Y" => Ok(Outcome::Draw),
-            "Z" => Ok(Outcome::Win),
-            _ => Err("Unable to Match".parse().unwrap()),
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let mut decoder = None;
+    let mut scoring = None;
+    let mut rules = None;
+    let mut tournament = false;
+    let mut rounds = None;
+    let mut seed = None;
+    let mut counter = false;
+    let mut constraints = Constraints::default();
+    let mut target_score = None;
+    let mut report = false;
+    let mut format = None;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--decoder" => decoder = Some(args.next().ok_or("Missing value for --decoder")?.parse()?),
+            "--scoring" => {
+                scoring = Some(args.next().ok_or("Missing value for --scoring")?.to_string());
+            }
+            "--rules" => rules = Some(args.next().ok_or("Missing value for --rules")?.to_string()),
+            "--tournament" => tournament = true,
+            "--rounds" => {
+                let n = args.next().ok_or("Missing value for --rounds")?;
+                rounds = Some(n.parse().map_err(|_| format!("Invalid value '{}' for --rounds", n))?);
+            }
+            "--seed" => {
+                let n = args.next().ok_or("Missing value for --seed")?;
+                seed = Some(n.parse().map_err(|_| format!("Invalid value '{}' for --seed", n))?);
+            }
+            "--counter" => counter = true,
+            "--max-draws" => {
+                let n = args.next().ok_or("Missing value for --max-draws")?;
+                let n = n.parse().map_err(|_| format!("Invalid value '{}' for --max-draws", n))?;
+                constraints.max_draws = Some(n);
+            }
+            "--max-run" => {
+                let n = args.next().ok_or("Missing value for --max-run")?;
+                match n.parse::<usize>() {
+                    Ok(n) if n > 0 => constraints.max_run = Some(n),
+                    _ => return Err(format!("Invalid value '{}' for --max-run", n)),
+                }
+            }
+            "--target-score" => {
+                let n = args.next().ok_or("Missing value for --target-score")?;
+                target_score =
+                    Some(n.parse().map_err(|_| format!("Invalid value '{}' for --target-score", n))?);
+            }
+            "--report" => report = true,
+            "--format" => {
+                format = match args.next().ok_or("Missing value for --format")?.as_str() {
+                    "table" => Some(Format::Table),
+                    "csv" => Some(Format::Csv),
+                    "json" => Some(Format::Json),
+                    other => return Err(format!("Unknown format '{}'", other)),
+                }
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown flag '{}'", flag)),
+            path => input = Some(path.to_string()),
         }
     }
-}
 
-struct Strategy {
-    desired_outcome: Outcome,
-    opponent: Play,
-    to_play: Play,
-}
+    if (rounds.is_some() || seed.is_some()) && !tournament {
+        return Err("--rounds and --seed only apply to --tournament".to_string());
+    }
 
-impl Strategy {
-    pub fn new(desired_outcome: Outcome, opponent: Play) -> Self {
-        Self {
-            desired_outcome,
-            opponent,
-            to_play: self::Strategy::what_to_play(desired_outcome, opponent),
-        }
+    if constraints != Constraints::default() && !counter {
+        return Err("--max-draws and --max-run only apply to --counter".to_string());
+    }
+    if format.is_some() && !report {
+        return Err("--format only applies to --report".to_string());
+    }
+    let modes = [counter, tournament, target_score.is_some(), report];
+    if modes.iter().filter(|&&mode| mode).count() > 1 {
+        return Err(
+            "--counter, --tournament, --target-score and --report cannot be used together"
+                .to_string(),
+        );
     }
 
-    fn what_to_play(desired_outcome: Outcome, opponent: Play) -> Play {
-        /// Return the Play required to achieve the desired result
-        match desired_outcome {
-            Outcome::Win => match opponent {
-                Play::Rock => Play::Paper,
-                Play::Paper => Play::Scissor,
-                Play::Scissor => Play::Rock,
-            },
-            Outcome::Draw => match opponent {
-                Play::Rock => Play::Rock,
-                Play::Paper => Play::Paper,
-                Play::Scissor => Play::Scissor,
-            },
-            Outcome::Loss => match opponent {
-                Play::Rock => Play::Scissor,
-                Play::Paper => Play::Rock,
-                Play::Scissor => Play::Paper,
-            },
-        }
+    if rules.is_some() && (decoder.is_some() || modes.contains(&true)) {
+        return Err(
+            "--rules cannot be used with --decoder, --counter, --tournament, --target-score or \
+             --report"
+                .to_string(),
+        );
     }
+
+    Ok(Options {
+        decoder,
+        scoring,
+        rules,
+        tournament: tournament.then(|| Tournament {
+            rounds: rounds.unwrap_or(1000),
+            seed: seed.unwrap_or(2022),
+        }),
+        counter: counter.then_some(constraints),
+        target_score,
+        report: report.then(|| format.unwrap_or(Format::Table)),
+        input: input.ok_or("Missing input file")?,
+    })
 }
 
-fn calculate_result(you: &Play, opponent: &Play) -> Outcome {
-    if you == opponent {
-        return Outcome::Draw;
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            exit(2)
+        }
+    };
+
+    // Reports may be read by other tools, so keep their output to the report itself.
+    if options.report.is_none() {
+        println!("In file {}", options.input);
     }
 
-    match you {
-        Play::Rock => {
-            if opponent == &Play::Paper {
-                return Outcome::Loss;
+    let rules = match &options.rules {
+        None => Rules::classic().clone(),
+        Some(path) => match fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|text| Rules::parse(&text))
+        {
+            Ok(rules) => rules,
+            Err(e) => {
+                eprintln!("error: {}: {}", path, e);
+                exit(1)
             }
-        }
-        Play::Paper => {
-            if opponent == &Play::Scissor {
-                return Outcome::Loss;
+        },
+    };
+
+    let scoring = match &options.scoring {
+        None => Scoring::new(&rules),
+        Some(path) => match fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|text| Scoring::parse(&text, &rules))
+        {
+            Ok(scoring) => scoring,
+            Err(e) => {
+                eprintln!("error: {}: {}", path, e);
+                exit(1)
             }
-        }
-        Play::Scissor => {
-            if opponent == &Play::Rock {
-                return Outcome::Loss;
+        },
+    };
+
+    if options.rules.is_some() {
+        match fs::read_to_string(&options.input)
+            .map_err(Error::from)
+            .and_then(|input| rules.parse_guide(&input))
+            .and_then(|guide| rules.score_guide(&guide, &scoring))
+        {
+            Ok(total) => println!("Second column as outcome: {}", total),
+            Err(e) => {
+                eprintln!("error: {}: {}", options.input, e);
+                exit(1)
             }
         }
+        return;
     }
 
-    Outcome::Win
-}
-
-fn score_for_played(played: &Play) -> i32 {
-    match played {
-        Play::Rock => 1,
-        Play::Paper => 2,
-        Play::Scissor => 3,
+    let rounds = match fs::read_to_string(&options.input)
+        .map_err(Error::from)
+        .and_then(|input| Day2::parse(&input))
+    {
+        Ok(rounds) => rounds,
+        Err(e) => {
+            eprintln!("error: {}: {}", options.input, e);
+            exit(1)
+        }
+    };
+
+    if let Some(tournament) = options.tournament {
+        let decoder = options.decoder.unwrap_or(Decoder::Play);
+        let guide = rounds.iter().map(|round| decoder.decode(round).to_play).collect();
+        let mut players: Vec<Box<dyn Player>> = vec![
+            Box::new(Guide::new(guide)),
+            Box::new(Random),
+            Box::new(FrequencyCounter::default()),
+            Box::new(LastMoveBeater::default()),
+            Box::new(Markov::default()),
+        ];
+
+        let standings = round_robin(&mut players, tournament.rounds, tournament.seed, &scoring);
+        print!("{}", Table(&standings));
+        return;
     }
-}
 
-fn score_for_outcome(result: Outcome) -> i32 {
-    match result {
-        Outcome::Loss => 0,
-        Outcome::Draw => 3,
-        Outcome::Win => 6,
+    if let Some(constraints) = options.counter {
+        let opponents: Vec<_> = rounds.iter().map(|round| round.opponent).collect();
+        match best_counter(&opponents, &constraints, &scoring) {
+            Ok(counter) => {
+                for (&opponent, &play) in opponents.iter().zip(&counter.plays) {
+                    println!("{}", Round::for_play(opponent, play));
+                }
+                println!("Total score: {}", counter.score);
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                exit(1)
+            }
+        }
+        return;
     }
-}
 
-fn score_for_match(you: Play, opponent: Play) -> i32 {
-    score_for_played(&you) + score_for_outcome(calculate_result(&you, &opponent))
-}
+    if let Some(format) = options.report {
+        let reports = breakdown(&rounds, options.decoder.unwrap_or(Decoder::Outcome), &scoring);
+        match format {
+            Format::Table => print!("{}", Breakdown(&reports)),
+            Format::Csv => print!("{}", to_csv(&reports)),
+            Format::Json => println!("{}", to_json(&reports)),
+        }
+        return;
+    }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    let file_path = &args[1];
-
-    let mut total_score: i32 = 0;
-
-    println!("In file {}", file_path);
-
-    if let Ok(lines) = read_lines(file_path) {
-        let mut count = 0;
-        for line in lines {
-            match line.ok() {
-                Some(strategy) => {
-                    let s = strategy.split_whitespace().collect::<Vec<&str>>();
-                    let strat = Strategy::new(
-                        /// We have to swap last and first here as I coded everything else to be
-                        /// you, opponent and the input is swapped. If I do this again, swap
-                        /// everything to match input order.
-                        Outcome::from_str(s.last().unwrap()).unwrap(),
-                        Play::from_str(s.first().unwrap()).unwrap(),
-                    );
-
-                    let score = score_for_match(strat.to_play, strat.opponent);
-                    total_score += score;
-
-                    dbg!(
-                        count,
-                        strat.opponent,
-                        strat.desired_outcome,
-                        strat.to_play,
-                        score,
-                        total_score
-                    );
-                }
-                None => {}
-            }
-            count += 1;
+    if let Some(target) = options.target_score {
+        let mappings = find_mappings(&rounds, target, &scoring);
+        if mappings.is_empty() {
+            eprintln!("error: no reading of X, Y and Z scores {}", target);
+            exit(1)
         }
+        for mapping in mappings {
+            println!("{}", mapping);
+        }
+        return;
+    }
 
-        println!("Total score: {:?}", total_score);
+    let decoders = match options.decoder {
+        Some(decoder) => vec![decoder],
+        None => Decoder::ALL.to_vec(),
+    };
+
+    for decoder in decoders {
+        println!("Second column as {}: {}", decoder.name(), total_score(&rounds, decoder, &scoring));
     }
 }
 
@@ -181,90 +309,122 @@ mod test {
 
     use super::*;
 
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
     #[test]
-    fn test_basic_game_rules() {
-        //Rock
-        assert_eq!(calculate_result(&Play::Rock, &Play::Scissor), Outcome::Win);
-        assert_eq!(calculate_result(&Play::Rock, &Play::Rock), Outcome::Draw);
-        assert_eq!(calculate_result(&Play::Rock, &Play::Paper), Outcome::Loss);
-
-        //Paper
-        assert_eq!(calculate_result(&Play::Paper, &Play::Rock), Outcome::Win);
-        assert_eq!(calculate_result(&Play::Paper, &Play::Paper), Outcome::Draw);
-        assert_eq!(
-            calculate_result(&Play::Paper, &Play::Scissor),
-            Outcome::Loss
-        );
+    fn test_parse_args_defaults_to_every_decoder() {
+        let options = parse_args(&args(&["input.txt"])).unwrap();
 
-        //Scissor
-        assert_eq!(calculate_result(&Play::Scissor, &Play::Paper), Outcome::Win);
-        assert_eq!(
-            calculate_result(&Play::Scissor, &Play::Scissor),
-            Outcome::Draw
-        );
-        assert_eq!(calculate_result(&Play::Scissor, &Play::Rock), Outcome::Loss);
+        assert_eq!(options.decoder, None);
+        assert_eq!(options.input, "input.txt");
     }
 
     #[test]
-    fn test_match_score() {
-        //Rock (1 point + outcome)
-        assert_eq!(score_for_match(Play::Rock, Play::Scissor), 1 + 6);
-        assert_eq!(score_for_match(Play::Rock, Play::Rock), 1 + 3);
-        assert_eq!(score_for_match(Play::Rock, Play::Paper), 1);
-
-        //Paper (2 points + outcome)
-        assert_eq!(score_for_match(Play::Paper, Play::Rock), 2 + 6);
-        assert_eq!(score_for_match(Play::Paper, Play::Paper), 2 + 3);
-        assert_eq!(score_for_match(Play::Paper, Play::Scissor), 2);
-
-        //Scissor (3 points + outcome)
-        assert_eq!(score_for_match(Play::Scissor, Play::Paper), 3 + 6);
-        assert_eq!(score_for_match(Play::Scissor, Play::Scissor), 3 + 3);
-        assert_eq!(score_for_match(Play::Scissor, Play::Rock), 3);
+    fn test_parse_args_reads_decoder() {
+        let options = parse_args(&args(&["--decoder", "play", "input.txt"])).unwrap();
+
+        assert_eq!(options.decoder, Some(Decoder::Play));
     }
 
     #[test]
-    fn test_to_play_calculations() {
-        // To Win
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Win, Play::Rock),
-            Play::Paper
-        );
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Win, Play::Paper),
-            Play::Scissor
-        );
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Win, Play::Scissor),
-            Play::Rock
-        );
+    fn test_parse_args_rejects_unknown_decoder() {
+        assert_eq!(parse_args(&args(&["--decoder", "shape", "input.txt"])).is_err(), true);
+    }
 
-        // To Draw
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Draw, Play::Rock),
-            Play::Rock
-        );
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Draw, Play::Paper),
-            Play::Paper
-        );
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Draw, Play::Scissor),
-            Play::Scissor
-        );
+    #[test]
+    fn test_parse_args_reads_scoring_file() {
+        let options = parse_args(&args(&["--scoring", "points.txt", "input.txt"])).unwrap();
 
-        // To Loose
-        assert_eq!(
-            Strategy::what_to_play(Outcome::Loss, Play::Rock),
-            Play::Scissor
-        );
+        assert_eq!(options.scoring, Some("points.txt".to_string()));
+    }
+
+    #[test]
+    fn test_parse_args_reads_rules_file() {
+        let options = parse_args(&args(&["--rules", "rpsls.txt", "input.txt"])).unwrap();
+
+        assert_eq!(options.rules, Some("rpsls.txt".to_string()));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_rules_with_other_modes() {
+        assert_eq!(parse_args(&args(&["--rules", "a", "--counter", "input.txt"])).is_err(), true);
         assert_eq!(
-            Strategy::what_to_play(Outcome::Loss, Play::Paper),
-            Play::Rock
+            parse_args(&args(&["--rules", "a", "--decoder", "play", "input.txt"])).is_err(),
+            true
         );
+    }
+
+    #[test]
+    fn test_parse_args_tournament_defaults() {
+        let options = parse_args(&args(&["--tournament", "input.txt"])).unwrap();
+
+        assert_eq!(options.tournament, Some(Tournament { rounds: 1000, seed: 2022 }));
+    }
+
+    #[test]
+    fn test_parse_args_reads_rounds_and_seed() {
+        let options =
+            parse_args(&args(&["--tournament", "--rounds", "10", "--seed", "7", "input.txt"])).unwrap();
+
+        assert_eq!(options.tournament, Some(Tournament { rounds: 10, seed: 7 }));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_rounds_without_tournament() {
+        assert_eq!(parse_args(&args(&["--rounds", "10", "input.txt"])).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_args_reads_counter_constraints() {
+        let options =
+            parse_args(&args(&["--counter", "--max-draws", "3", "--max-run", "2", "input.txt"])).unwrap();
+
+        assert_eq!(options.counter, Some(Constraints { max_draws: Some(3), max_run: Some(2) }));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_constraints_without_counter() {
+        assert_eq!(parse_args(&args(&["--max-run", "2", "input.txt"])).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_args_rejects_zero_max_run() {
+        assert_eq!(parse_args(&args(&["--counter", "--max-run", "0", "input.txt"])).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_args_reads_target_score() {
+        let options = parse_args(&args(&["--target-score", "-4", "input.txt"])).unwrap();
+
+        assert_eq!(options.target_score, Some(-4));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_target_score_with_counter() {
         assert_eq!(
-            Strategy::what_to_play(Outcome::Loss, Play::Scissor),
-            Play::Paper
+            parse_args(&args(&["--counter", "--target-score", "15", "input.txt"])).is_err(),
+            true
         );
     }
+
+    #[test]
+    fn test_parse_args_report_defaults_to_table() {
+        let options = parse_args(&args(&["--report", "input.txt"])).unwrap();
+
+        assert_eq!(options.report, Some(Format::Table));
+    }
+
+    #[test]
+    fn test_parse_args_reads_report_format() {
+        let options = parse_args(&args(&["--report", "--format", "csv", "input.txt"])).unwrap();
+
+        assert_eq!(options.report, Some(Format::Csv));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_format_without_report() {
+        assert_eq!(parse_args(&args(&["--format", "json", "input.txt"])).is_err(), true);
+    }
 }
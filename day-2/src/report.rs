@@ -0,0 +1,222 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+use crate::{Decoder, Outcome, Play, Round, Scoring};
+
+/// What happened in one round of following the guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RoundReport {
+    /// 1-based position of the round in the guide.
+    pub round: usize,
+    pub opponent: Play,
+    pub outcome: Outcome,
+    pub played: Play,
+    pub score: i64,
+    /// Score of this round and every one before it.
+    pub total: i64,
+}
+
+/// Follow the guide, reading it with `decoder`, and report every round.
+pub fn breakdown(rounds: &[Round], decoder: Decoder, scoring: &Scoring) -> Vec<RoundReport> {
+    let mut total = 0;
+    rounds
+        .iter()
+        .enumerate()
+        .map(|(i, round)| {
+            let strat = decoder.decode(round);
+            let score = scoring.score_match(strat.to_play, strat.opponent);
+            total += score;
+            RoundReport {
+                round: i + 1,
+                opponent: strat.opponent,
+                outcome: strat.desired_outcome,
+                played: strat.to_play,
+                score,
+                total,
+            }
+        })
+        .collect()
+}
+
+/// How often each play led to each outcome.
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Summary {
+    /// `counts[play][outcome]`, indexed like [`Play::ALL`] and [`Outcome::ALL`].
+    #[serde(serialize_with = "by_name")]
+    pub counts: [[usize; 3]; 3],
+}
+
+/// Counts as an object keyed by play, then by outcome.
+fn by_name<S: Serializer>(counts: &[[usize; 3]; 3], serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Outcomes {
+        loss: usize,
+        draw: usize,
+        win: usize,
+    }
+
+    serializer.collect_map(Play::ALL.map(|play| {
+        let [loss, draw, win] = Outcome::ALL.map(|outcome| counts[play.index()][outcome.index()]);
+        (play, Outcomes { loss, draw, win })
+    }))
+}
+
+impl Summary {
+    pub fn new(reports: &[RoundReport]) -> Self {
+        let mut summary = Summary::default();
+        for report in reports {
+            summary.counts[report.played.index()][report.outcome.index()] += 1;
+        }
+        summary
+    }
+
+    pub fn count(&self, played: Play, outcome: Outcome) -> usize {
+        self.counts[played.index()][outcome.index()]
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a summary is always valid JSON")
+    }
+}
+
+/// Renders the summary as a table with a row per play.
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Played   |  Wins | Draws | Losses")?;
+        writeln!(f, "---------+-------+-------+-------")?;
+        for play in Play::ALL {
+            writeln!(
+                f,
+                "{:<8} | {:>5} | {:>5} | {:>6}",
+                play.name(),
+                self.count(play, Outcome::Win),
+                self.count(play, Outcome::Draw),
+                self.count(play, Outcome::Loss)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+const CSV_HEADER: &str = "round,opponent,outcome,played,score,total";
+
+/// The rounds as CSV, one row per round under a header.
+pub fn to_csv(reports: &[RoundReport]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for r in reports {
+        csv += &format!(
+            "{},{},{},{},{},{}\n",
+            r.round,
+            r.opponent.name(),
+            r.outcome.name(),
+            r.played.name(),
+            r.score,
+            r.total
+        );
+    }
+    csv
+}
+
+/// The rounds and their summary as a single JSON object.
+pub fn to_json(reports: &[RoundReport]) -> String {
+    #[derive(Serialize)]
+    struct Export<'a> {
+        rounds: &'a [RoundReport],
+        summary: Summary,
+    }
+
+    let export = Export { rounds: reports, summary: Summary::new(reports) };
+    serde_json::to_string(&export).expect("a report is always valid JSON")
+}
+
+/// Renders the rounds as a table followed by their summary.
+pub struct Breakdown<'a>(pub &'a [RoundReport]);
+
+impl fmt::Display for Breakdown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, " Round | Opponent | Outcome | Played   | Score |  Total")?;
+        writeln!(f, "-------+----------+---------+----------+-------+-------")?;
+        for r in self.0 {
+            writeln!(
+                f,
+                "{:>6} | {:<8} | {:<7} | {:<8} | {:>5} | {:>6}",
+                r.round,
+                r.opponent.name(),
+                r.outcome.name(),
+                r.played.name(),
+                r.score,
+                r.total
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "{}", Summary::new(self.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_guide;
+
+    fn example() -> Vec<RoundReport> {
+        let rounds = parse_guide("A Y\nB X\nC Z\n").unwrap();
+        breakdown(&rounds, Decoder::Outcome, &Scoring::default())
+    }
+
+    #[test]
+    fn test_breakdown_keeps_a_running_total() {
+        let reports = example();
+
+        assert_eq!(
+            reports[1],
+            RoundReport {
+                round: 2,
+                opponent: Play::Paper,
+                outcome: Outcome::Loss,
+                played: Play::Rock,
+                score: 1,
+                total: 5,
+            }
+        );
+        assert_eq!(reports.last().unwrap().total, 12);
+    }
+
+    #[test]
+    fn test_summary_counts_outcomes_by_play() {
+        let summary = Summary::new(&example());
+
+        assert_eq!(summary.count(Play::Rock, Outcome::Draw), 1);
+        assert_eq!(summary.count(Play::Rock, Outcome::Win), 1);
+        assert_eq!(summary.count(Play::Rock, Outcome::Loss), 1);
+        assert_eq!(summary.counts.iter().flatten().sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(
+            to_csv(&example()),
+            "round,opponent,outcome,played,score,total\n\
+             1,rock,draw,rock,4,4\n\
+             2,paper,loss,rock,1,5\n\
+             3,scissors,win,rock,7,12\n"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = to_json(&example()[..1]);
+
+        assert_eq!(
+            json,
+            "{\"rounds\":[{\"round\":1,\"opponent\":\"rock\",\"outcome\":\"draw\",\
+             \"played\":\"rock\",\"score\":4,\"total\":4}],\
+             \"summary\":{\"rock\":{\"loss\":0,\"draw\":1,\"win\":0},\
+             \"paper\":{\"loss\":0,\"draw\":0,\"win\":0},\
+             \"scissors\":{\"loss\":0,\"draw\":0,\"win\":0}}}"
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use common::{tokens, Error};
+
+use crate::{Outcome, Scoring};
+
+/// A game of picking moves where which move beats which is data rather than code. Moves are
+/// referred to by their index in the order they are listed.
+///
+/// The puzzle's guide, and everything built on [`Play`](crate::Play), is played by
+/// [`Rules::classic`]. Other games are loaded with [`Rules::parse`] and played by move index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    moves: Vec<String>,
+    /// `beats[a][b]` is true when move `a` beats move `b`.
+    beats: Vec<Vec<bool>>,
+}
+
+impl Rules {
+    /// Build rules from the moves and every `(winner, loser)` pair. Pairs that are not listed
+    /// are draws.
+    pub fn new(moves: &[&str], beats: &[(&str, &str)]) -> Result<Self, String> {
+        if moves.is_empty() {
+            return Err("a game needs at least one move".to_string());
+        }
+
+        let mut rules = Self {
+            moves: moves.iter().map(|name| name.to_string()).collect(),
+            beats: vec![vec![false; moves.len()]; moves.len()],
+        };
+        for (i, name) in moves.iter().enumerate() {
+            if moves[..i].contains(name) {
+                return Err(format!("move '{}' is listed twice", name));
+            }
+        }
+
+        for &(winner, loser) in beats {
+            rules.add_beat(winner, loser)?;
+        }
+        Ok(rules)
+    }
+
+    fn add_beat(&mut self, winner: &str, loser: &str) -> Result<(), String> {
+        let a = self.find(winner).ok_or(format!("unknown move '{}'", winner))?;
+        let b = self.find(loser).ok_or(format!("unknown move '{}'", loser))?;
+        if a == b {
+            return Err(format!("move '{}' cannot beat itself", winner));
+        }
+        if self.beats[b][a] {
+            return Err(format!("'{}' and '{}' cannot both beat each other", winner, loser));
+        }
+        self.beats[a][b] = true;
+        Ok(())
+    }
+
+    /// A balanced tournament of an odd number of moves, where each move beats the half of the
+    /// others just before it in the list, wrapping around. Rock, paper, scissors is the
+    /// three-move case, and rock, Spock, paper, lizard, scissors the five-move one.
+    pub fn cyclic(moves: &[&str]) -> Result<Self, String> {
+        let n = moves.len();
+        if n.is_multiple_of(2) {
+            return Err(format!("a cyclic game needs an odd number of moves, found {}", n));
+        }
+
+        let beats: Vec<(&str, &str)> = (0..n)
+            .flat_map(|a| (1..=n / 2).map(move |step| (moves[a], moves[(a + n - step) % n])))
+            .collect();
+        Self::new(moves, &beats)
+    }
+
+    /// Rock, paper, scissors, in the order the puzzle scores them.
+    pub fn classic() -> &'static Self {
+        static CLASSIC: OnceLock<Rules> = OnceLock::new();
+        CLASSIC.get_or_init(|| Self::cyclic(&["rock", "paper", "scissors"]).unwrap())
+    }
+
+    /// Read a game from a `moves = a, b, c` line and a `<move> beats <move>, <move>` line for
+    /// each move that beats any, or from a single `cyclic = a, b, c` line for a balanced game
+    /// as made by [`Rules::cyclic`]. `#` starts a comment.
+    pub fn parse(input: &str) -> common::Result<Self> {
+        let mut listed = None;
+        let mut beats = vec![];
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = tokens(line);
+            let Some((column, first)) = words.next() else {
+                continue;
+            };
+
+            if let Some((key, list)) = line.split_once('=') {
+                let key = key.trim();
+                if key != "moves" && key != "cyclic" {
+                    return Err(Error::parse(i + 1, column, key, "expected moves or cyclic"));
+                }
+                if listed.is_some() {
+                    return Err(Error::parse(i + 1, column, key, "the moves are already listed"));
+                }
+                listed = Some((i + 1, key == "cyclic", names(line, list, i + 1)?));
+                continue;
+            }
+
+            match words.next() {
+                Some((_, "beats")) => {}
+                Some((column, word)) => {
+                    return Err(Error::parse(i + 1, column, word, "expected beats"));
+                }
+                None => {
+                    return Err(Error::parse(i + 1, column, first, "expected <move> beats <move>"));
+                }
+            }
+            let Some((column, _)) = words.next() else {
+                return Err(Error::parse(i + 1, line.len() + 1, "", "missing the moves beaten"));
+            };
+            for loser in names(line, &line[column - 1..], i + 1)? {
+                beats.push((i + 1, first, loser));
+            }
+        }
+
+        let Some((line, cyclic, moves)) = listed else {
+            return Err(Error::Semantic("no moves = or cyclic = line lists the moves".to_string()));
+        };
+        let at = |line: usize| move |e: String| Error::Semantic(format!("line {}: {}", line, e));
+        if cyclic {
+            if let Some(&(line, _, _)) = beats.first() {
+                let message = "a cyclic game already says which move beats which";
+                return Err(at(line)(message.to_string()));
+            }
+            return Self::cyclic(&moves).map_err(at(line));
+        }
+
+        let mut rules = Self::new(&moves, &[]).map_err(at(line))?;
+        for (line, winner, loser) in beats {
+            rules.add_beat(winner, loser).map_err(at(line))?;
+        }
+        Ok(rules)
+    }
+
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.moves.iter().position(|known| known == name)
+    }
+
+    pub fn beats(&self, a: usize, b: usize) -> bool {
+        self.beats[a][b]
+    }
+
+    /// The outcome for you of playing `you` against `opponent`.
+    pub fn outcome(&self, you: usize, opponent: usize) -> Outcome {
+        if self.beats[you][opponent] {
+            Outcome::Win
+        } else if self.beats[opponent][you] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The first move that gives the desired outcome against `opponent`, if there is one.
+    pub fn what_to_play(&self, desired_outcome: Outcome, opponent: usize) -> Option<usize> {
+        if desired_outcome == Outcome::Draw {
+            return Some(opponent);
+        }
+        (0..self.moves.len()).find(|&you| self.outcome(you, opponent) == desired_outcome)
+    }
+
+    /// Read a guide to this game: a line per round, with the opponent's move, by name or by
+    /// letter from `A`, then `X`, `Y` or `Z` for the round to lose, draw or win.
+    pub fn parse_guide(&self, input: &str) -> common::Result<Vec<(usize, Outcome)>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| self.parse_turn(line).map_err(|e| e.at_line(i + 1)))
+            .collect()
+    }
+
+    fn parse_turn(&self, line: &str) -> common::Result<(usize, Outcome)> {
+        let mut columns = tokens(line);
+        let end = line.len() + 1;
+
+        let (column, text) = columns
+            .next()
+            .ok_or_else(|| Error::parse(1, end, "", "missing opponent move"))?;
+        let opponent = self
+            .find(text)
+            .or_else(|| match text.as_bytes() {
+                &[letter @ b'A'..=b'Z'] => Some((letter - b'A') as usize),
+                _ => None,
+            })
+            .filter(|&opponent| opponent < self.moves.len())
+            .ok_or_else(|| Error::parse(1, column, text, "expected a move, by name or letter"))?;
+
+        let (column, text) = columns
+            .next()
+            .ok_or_else(|| Error::parse(1, end, "", "missing outcome"))?;
+        let outcome = Outcome::from_str(text).map_err(|e| Error::parse(1, column, text, e))?;
+
+        if let Some((column, text)) = columns.next() {
+            return Err(Error::parse(1, column, text, "unexpected text after outcome"));
+        }
+        Ok((opponent, outcome))
+    }
+
+    /// Total score of playing, in every round of the guide, the first move that reaches the
+    /// outcome asked for. Fails if no move does.
+    pub fn score_guide(
+        &self,
+        guide: &[(usize, Outcome)],
+        scoring: &Scoring,
+    ) -> common::Result<i64> {
+        guide
+            .iter()
+            .enumerate()
+            .map(|(i, &(opponent, outcome))| {
+                let you = self.what_to_play(outcome, opponent).ok_or_else(|| {
+                    Error::Semantic(format!(
+                        "round {}: no move gives a {} against {}",
+                        i + 1,
+                        outcome.name(),
+                        self.moves[opponent]
+                    ))
+                })?;
+                Ok(scoring.score_moves(self, you, opponent))
+            })
+            .sum()
+    }
+}
+
+/// The comma separated names in `list`, which is part of `line`.
+fn names<'a>(line: &str, list: &'a str, number: usize) -> common::Result<Vec<&'a str>> {
+    list.split(',')
+        .map(str::trim)
+        .map(|name| {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                let column = name.as_ptr() as usize - line.as_ptr() as usize + 1;
+                return Err(Error::parse(number, column, name, "expected a one-word move name"));
+            }
+            Ok(name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn rpsls() -> Rules {
+        Rules::cyclic(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).unwrap()
+    }
+
+    #[test]
+    fn test_classic_rules() {
+        let rules = Rules::classic();
+        let (rock, paper, scissors) = (0, 1, 2);
+
+        assert_eq!(rules.outcome(rock, scissors), Outcome::Win);
+        assert_eq!(rules.outcome(paper, rock), Outcome::Win);
+        assert_eq!(rules.outcome(scissors, paper), Outcome::Win);
+        assert_eq!(rules.outcome(rock, paper), Outcome::Loss);
+        assert_eq!(rules.outcome(paper, paper), Outcome::Draw);
+    }
+
+    #[test]
+    fn test_cyclic_rock_paper_scissors_lizard_spock() {
+        let rules = rpsls();
+        let id = |name| rules.find(name).unwrap();
+
+        assert_eq!(rules.beats(id("Scissors"), id("Paper")), true);
+        assert_eq!(rules.beats(id("Scissors"), id("Lizard")), true);
+        assert_eq!(rules.beats(id("Lizard"), id("Spock")), true);
+        assert_eq!(rules.beats(id("Spock"), id("Rock")), true);
+        assert_eq!(rules.beats(id("Rock"), id("Lizard")), true);
+        assert_eq!(rules.beats(id("Paper"), id("Spock")), true);
+        assert_eq!(rules.outcome(id("Lizard"), id("Rock")), Outcome::Loss);
+    }
+
+    #[test]
+    fn test_every_cyclic_move_beats_half_the_others() {
+        let rules = Rules::cyclic(&["a", "b", "c", "d", "e", "f", "g"]).unwrap();
+
+        for a in 0..7 {
+            assert_eq!((0..7).filter(|&b| rules.beats(a, b)).count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_cyclic_needs_odd_moves() {
+        assert_eq!(Rules::cyclic(&["a", "b"]).is_err(), true);
+    }
+
+    #[test]
+    fn test_new_rejects_contradictions() {
+        assert_eq!(Rules::new(&["a", "b"], &[("a", "b"), ("b", "a")]).is_err(), true);
+        assert_eq!(Rules::new(&["a"], &[("a", "a")]).is_err(), true);
+        assert_eq!(Rules::new(&["a"], &[("a", "z")]).is_err(), true);
+        assert_eq!(Rules::new(&["a", "a"], &[]).is_err(), true);
+    }
+
+    #[test]
+    fn test_unlisted_pairs_draw() {
+        let rules = Rules::new(&["a", "b", "c"], &[("a", "b")]).unwrap();
+
+        assert_eq!(rules.outcome(0, 2), Outcome::Draw);
+    }
+
+    #[test]
+    fn test_what_to_play_follows_the_relation() {
+        let rules = rpsls();
+        let rock = rules.find("Rock").unwrap();
+
+        let win = rules.what_to_play(Outcome::Win, rock).unwrap();
+        let lose = rules.what_to_play(Outcome::Loss, rock).unwrap();
+
+        assert_eq!(rules.outcome(win, rock), Outcome::Win);
+        assert_eq!(rules.outcome(lose, rock), Outcome::Loss);
+        assert_eq!(rules.what_to_play(Outcome::Draw, rock), Some(rock));
+    }
+
+    #[test]
+    fn test_what_to_play_without_an_answer() {
+        let rules = Rules::new(&["a", "b"], &[("a", "b")]).unwrap();
+
+        assert_eq!(rules.what_to_play(Outcome::Win, 0), None);
+    }
+
+    #[test]
+    fn test_parse_cyclic_game() {
+        let input = "# the big bang theory\ncyclic = Rock, Spock, Paper, Lizard, Scissors\n";
+        let rules = Rules::parse(input).unwrap();
+
+        assert_eq!(rules, rpsls());
+    }
+
+    #[test]
+    fn test_parse_listed_beats() {
+        let input = "moves = rock, paper, scissors, well\n\
+                     rock beats scissors\n\
+                     paper beats rock, well\n\
+                     scissors beats paper\n\
+                     well beats rock, scissors\n";
+        let rules = Rules::parse(input).unwrap();
+        let id = |name| rules.find(name).unwrap();
+
+        assert_eq!(rules.moves().len(), 4);
+        assert_eq!(rules.outcome(id("well"), id("scissors")), Outcome::Win);
+        assert_eq!(rules.outcome(id("well"), id("paper")), Outcome::Loss);
+        assert_eq!(rules.outcome(id("rock"), id("rock")), Outcome::Draw);
+    }
+
+    #[test]
+    fn test_parse_reports_where_it_went_wrong() {
+        assert_eq!(
+            Rules::parse("moves = a, b\na loses b\n").err().unwrap().to_string(),
+            "line 2, column 3: expected beats (found 'loses')"
+        );
+        assert_eq!(
+            Rules::parse("moves = a, b c\n").err().unwrap().to_string(),
+            "line 1, column 12: expected a one-word move name (found 'b c')"
+        );
+        assert_eq!(
+            Rules::parse("moves = a, b\na beats b\nb beats a\n").err().unwrap().to_string(),
+            "line 3: 'b' and 'a' cannot both beat each other"
+        );
+        assert_eq!(Rules::parse("a beats b\n").is_err(), true);
+        assert_eq!(Rules::parse("cyclic = a, b, c\na beats b\n").is_err(), true);
+    }
+
+    #[test]
+    fn test_score_guide_of_a_larger_game() {
+        let rules = rpsls();
+        let guide = rules.parse_guide("Spock Z\nD X\nA Y\n").unwrap();
+
+        // Paper beats Spock for 3 + 6, Spock is the first move to lose to lizard for 2, and
+        // rock draws for 1 + 3.
+        assert_eq!(guide, vec![(1, Outcome::Win), (3, Outcome::Loss), (0, Outcome::Draw)]);
+        assert_eq!(rules.score_guide(&guide, &Scoring::new(&rules)).unwrap(), 9 + 2 + 4);
+    }
+
+    #[test]
+    fn test_parse_guide_rejects_moves_outside_the_game() {
+        assert_eq!(
+            Rules::classic().parse_guide("A X\nD X\n").err().unwrap().to_string(),
+            "line 2, column 1: expected a move, by name or letter (found 'D')"
+        );
+    }
+
+    #[test]
+    fn test_score_guide_fails_on_an_outcome_out_of_reach() {
+        let rules = Rules::new(&["a", "b"], &[("a", "b")]).unwrap();
+        let guide = rules.parse_guide("a Z\n").unwrap();
+
+        assert_eq!(
+            rules.score_guide(&guide, &Scoring::new(&rules)).err().unwrap().to_string(),
+            "round 1: no move gives a win against a"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use common::{Error, Result};
+
+use crate::{Outcome, Play, Rules};
+
+/// Points for the move played and for the outcome of a round, in a game played by some
+/// [`Rules`].
+///
+/// Loaded from `key = value` lines, where the keys are the names of the moves and `loss`,
+/// `draw` and `win`. Keys left out keep the puzzle's values, where the moves score 1, 2, 3 and
+/// on in the order the rules list them, and `#` starts a comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoring {
+    /// Points for playing each move, in the order the rules list them.
+    pub played: Vec<i32>,
+    pub loss: i32,
+    pub draw: i32,
+    pub win: i32,
+}
+
+/// The puzzle's points for rock, paper and scissors.
+impl Default for Scoring {
+    fn default() -> Self {
+        Self::new(Rules::classic())
+    }
+}
+
+impl Scoring {
+    /// The puzzle's points, for a game played by `rules`.
+    pub fn new(rules: &Rules) -> Self {
+        Self {
+            played: (1..=rules.moves().len() as i32).collect(),
+            loss: 0,
+            draw: 3,
+            win: 6,
+        }
+    }
+
+    /// Read the points for a game played by `rules`.
+    pub fn parse(input: &str, rules: &Rules) -> Result<Self> {
+        let mut scoring = Scoring::new(rules);
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let column = line.len() - line.trim_start().len() + 1;
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(Error::parse(i + 1, column, line.trim(), "expected key = value"));
+            };
+
+            let key = key.trim();
+            let field = match key {
+                "loss" => &mut scoring.loss,
+                "draw" => &mut scoring.draw,
+                "win" => &mut scoring.win,
+                _ => match rules.find(key) {
+                    Some(played) => &mut scoring.played[played],
+                    None => {
+                        let mut keys = rules.moves().to_vec();
+                        keys.extend(["loss", "draw"].map(String::from));
+                        let message = format!("expected one of {} or win", keys.join(", "));
+                        return Err(Error::parse(i + 1, column, key, message));
+                    }
+                },
+            };
+
+            let column = line.len() - value.trim_start().len() + 1;
+            let value = value.trim();
+            *field = value
+                .parse()
+                .map_err(|_| Error::parse(i + 1, column, value, "expected a whole number of points"))?;
+        }
+
+        Ok(scoring)
+    }
+
+    pub fn played(&self, played: Play) -> i64 {
+        self.played[played.index()] as i64
+    }
+
+    pub fn outcome(&self, result: Outcome) -> i64 {
+        let points = match result {
+            Outcome::Loss => self.loss,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win,
+        };
+        points as i64
+    }
+
+    /// Points for playing move `you` against move `opponent` under `rules`. Totals of these
+    /// are kept as `i64` too, as points read from a file can be as large as an `i32` allows.
+    pub fn score_moves(&self, rules: &Rules, you: usize, opponent: usize) -> i64 {
+        self.played[you] as i64 + self.outcome(rules.outcome(you, opponent))
+    }
+
+    /// Points for one round of rock, paper, scissors.
+    pub fn score_match(&self, you: Play, opponent: Play) -> i64 {
+        self.score_moves(Rules::classic(), you.index(), opponent.index())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_default_is_the_puzzle_scoring() {
+        let scoring = Scoring::default();
+
+        assert_eq!(scoring.played, vec![1, 2, 3]);
+        assert_eq!([scoring.loss, scoring.draw, scoring.win], [0, 3, 6]);
+    }
+
+    #[test]
+    fn test_parse_overrides_given_keys() {
+        let input = "# harsher losses\nloss = -6\n\n  win=10  # and bigger wins\n";
+        let scoring = Scoring::parse(input, Rules::classic()).unwrap();
+
+        assert_eq!(scoring, Scoring { loss: -6, win: 10, ..Scoring::default() });
+    }
+
+    #[test]
+    fn test_parse_reports_unknown_key() {
+        assert_eq!(
+            Scoring::parse("rock = 1\nlizard = 5\n", Rules::classic()).err().unwrap().to_string(),
+            "line 2, column 1: expected one of rock, paper, scissors, loss, draw or win (found 'lizard')"
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_bad_value() {
+        assert_eq!(
+            Scoring::parse("draw = lots", Rules::classic()).err().unwrap().to_string(),
+            "line 1, column 8: expected a whole number of points (found 'lots')"
+        );
+    }
+
+    #[test]
+    fn test_parse_requires_equals() {
+        assert_eq!(Scoring::parse("win 6", Rules::classic()).is_err(), true);
+    }
+
+    #[test]
+    fn test_score_match_uses_the_table() {
+        let scoring = Scoring { played: vec![1, 20, 3], win: 100, ..Scoring::default() };
+
+        assert_eq!(scoring.score_match(Play::Paper, Play::Rock), 120);
+        assert_eq!(scoring.score_match(Play::Rock, Play::Paper), 1);
+    }
+
+    #[test]
+    fn test_parse_keys_are_the_moves_of_the_rules() {
+        let rules = Rules::cyclic(&["rock", "spock", "paper", "lizard", "scissors"]).unwrap();
+        let scoring = Scoring::parse("spock = 10\nlizard = 20\n", &rules).unwrap();
+
+        assert_eq!(scoring.played, vec![1, 10, 3, 20, 5]);
+        assert_eq!(scoring.score_moves(&rules, 1, 0), 10 + 6);
+        assert_eq!(
+            Scoring::parse("well = 1", &rules).err().unwrap().to_string(),
+            "line 1, column 1: expected one of rock, spock, paper, lizard, scissors, loss, draw or \
+             win (found 'well')"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::{calculate_result, Outcome, Play, Rng, Scoring, Strategy};
+
+/// Something that picks a play each round and learns from what its opponent did.
+pub trait Player {
+    fn name(&self) -> String;
+
+    fn play(&mut self, rng: &mut Rng) -> Play;
+
+    /// Told after every round what each side played.
+    fn observe(&mut self, _mine: Play, _theirs: Play) {}
+
+    /// Forget everything learnt, ready for a new opponent.
+    fn reset(&mut self) {}
+}
+
+fn random_play(rng: &mut Rng) -> Play {
+    Play::from_index(rng.below(3))
+}
+
+fn beating(play: Play) -> Play {
+    Strategy::what_to_play(Outcome::Win, play)
+}
+
+/// Plays the shapes of a strategy guide in order, starting over when it runs out.
+pub struct Guide {
+    plays: Vec<Play>,
+    next: usize,
+}
+
+impl Guide {
+    pub fn new(plays: Vec<Play>) -> Self {
+        Self { plays, next: 0 }
+    }
+}
+
+impl Player for Guide {
+    fn name(&self) -> String {
+        "guide".to_string()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Play {
+        if self.plays.is_empty() {
+            return random_play(rng);
+        }
+        let play = self.plays[self.next % self.plays.len()];
+        self.next += 1;
+        play
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+/// Picks every shape with equal chance.
+pub struct Random;
+
+impl Player for Random {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Play {
+        random_play(rng)
+    }
+}
+
+/// Plays what beats the opponent's most common shape so far.
+#[derive(Default)]
+pub struct FrequencyCounter {
+    seen: [usize; 3],
+}
+
+impl Player for FrequencyCounter {
+    fn name(&self) -> String {
+        "frequency".to_string()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Play {
+        let most = *self.seen.iter().max().unwrap();
+        if most == 0 {
+            return random_play(rng);
+        }
+        let favourite = self.seen.iter().position(|&count| count == most).unwrap();
+        beating(Play::from_index(favourite))
+    }
+
+    fn observe(&mut self, _mine: Play, theirs: Play) {
+        self.seen[theirs.index()] += 1;
+    }
+
+    fn reset(&mut self) {
+        self.seen = [0; 3];
+    }
+}
+
+/// Plays what would have beaten the opponent's last shape.
+#[derive(Default)]
+pub struct LastMoveBeater {
+    last: Option<Play>,
+}
+
+impl Player for LastMoveBeater {
+    fn name(&self) -> String {
+        "last-move".to_string()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Play {
+        match self.last {
+            Some(last) => beating(last),
+            None => random_play(rng),
+        }
+    }
+
+    fn observe(&mut self, _mine: Play, theirs: Play) {
+        self.last = Some(theirs);
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Counts which shape the opponent follows each shape with, predicts their next one from
+/// their last, and plays what beats it.
+#[derive(Default)]
+pub struct Markov {
+    transitions: [[usize; 3]; 3],
+    last: Option<Play>,
+}
+
+impl Player for Markov {
+    fn name(&self) -> String {
+        "markov".to_string()
+    }
+
+    fn play(&mut self, rng: &mut Rng) -> Play {
+        let Some(last) = self.last else {
+            return random_play(rng);
+        };
+        let next = &self.transitions[last.index()];
+        let most = *next.iter().max().unwrap();
+        if most == 0 {
+            return random_play(rng);
+        }
+        let predicted = next.iter().position(|&count| count == most).unwrap();
+        beating(Play::from_index(predicted))
+    }
+
+    fn observe(&mut self, _mine: Play, theirs: Play) {
+        if let Some(last) = self.last {
+            self.transitions[last.index()][theirs.index()] += 1;
+        }
+        self.last = Some(theirs);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// How one player fared across every round it played.
+#[derive(Debug, Default, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub score: i64,
+    /// How many rounds scored each number of points.
+    pub scores: BTreeMap<i64, usize>,
+}
+
+impl Standing {
+    pub fn rounds(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.rounds().max(1) as f64
+    }
+
+    fn record(&mut self, result: Outcome, score: i64) {
+        match result {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+        self.score += score;
+        *self.scores.entry(score).or_default() += 1;
+    }
+}
+
+/// Play every pair of players against each other for `rounds` rounds. Each pairing gets its
+/// own generator derived from `seed`, so the whole tournament replays from the seed alone.
+pub fn round_robin(
+    players: &mut [Box<dyn Player>],
+    rounds: usize,
+    seed: u64,
+    scoring: &Scoring,
+) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = players
+        .iter()
+        .map(|player| Standing {
+            name: player.name(),
+            ..Standing::default()
+        })
+        .collect();
+    let mut seeds = Rng::new(seed);
+
+    for a in 0..players.len() {
+        for b in a + 1..players.len() {
+            let mut rng = Rng::new(seeds.next_u64());
+            players[a].reset();
+            players[b].reset();
+
+            for _ in 0..rounds {
+                let play_a = players[a].play(&mut rng);
+                let play_b = players[b].play(&mut rng);
+                players[a].observe(play_a, play_b);
+                players[b].observe(play_b, play_a);
+
+                for (i, mine, theirs) in [(a, play_a, play_b), (b, play_b, play_a)] {
+                    let result = calculate_result(&mine, &theirs);
+                    standings[i].record(result, scoring.score_match(mine, theirs));
+                }
+            }
+        }
+    }
+
+    standings
+}
+
+/// Renders standings as a table, best win rate first.
+pub struct Table<'a>(pub &'a [Standing]);
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut standings: Vec<&Standing> = self.0.iter().collect();
+        standings.sort_by(|a, b| b.win_rate().total_cmp(&a.win_rate()));
+
+        writeln!(f, "Player     |  Win% | Draw% | Loss% | Mean score | Round scores")?;
+        writeln!(f, "-----------+-------+-------+-------+------------+-------------")?;
+        for s in standings {
+            let rounds = s.rounds().max(1) as f64;
+            let scores: Vec<String> =
+                s.scores.iter().map(|(score, n)| format!("{}:{}", score, n)).collect();
+            writeln!(
+                f,
+                "{:<10} | {:>5.1} | {:>5.1} | {:>5.1} | {:>10.2} | {}",
+                s.name,
+                100.0 * s.wins as f64 / rounds,
+                100.0 * s.draws as f64 / rounds,
+                100.0 * s.losses as f64 / rounds,
+                s.score as f64 / rounds,
+                scores.join(" ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn players() -> Vec<Box<dyn Player>> {
+        vec![
+            Box::new(Guide::new(vec![Play::Rock, Play::Rock, Play::Paper])),
+            Box::new(Random),
+            Box::new(FrequencyCounter::default()),
+            Box::new(LastMoveBeater::default()),
+            Box::new(Markov::default()),
+        ]
+    }
+
+    #[test]
+    fn test_guide_repeats_its_plays() {
+        let mut guide = Guide::new(vec![Play::Rock, Play::Paper]);
+        let mut rng = Rng::new(0);
+
+        let plays: Vec<Play> = (0..3).map(|_| guide.play(&mut rng)).collect();
+
+        assert_eq!(plays, vec![Play::Rock, Play::Paper, Play::Rock]);
+    }
+
+    #[test]
+    fn test_frequency_counter_beats_favourite() {
+        let mut player = FrequencyCounter::default();
+        player.observe(Play::Rock, Play::Scissor);
+        player.observe(Play::Rock, Play::Scissor);
+        player.observe(Play::Rock, Play::Paper);
+
+        assert_eq!(player.play(&mut Rng::new(0)), Play::Rock);
+    }
+
+    #[test]
+    fn test_last_move_beater() {
+        let mut player = LastMoveBeater::default();
+        player.observe(Play::Rock, Play::Paper);
+
+        assert_eq!(player.play(&mut Rng::new(0)), Play::Scissor);
+    }
+
+    #[test]
+    fn test_markov_predicts_from_transitions() {
+        let mut player = Markov::default();
+        for theirs in [Play::Rock, Play::Paper, Play::Rock, Play::Paper, Play::Rock] {
+            player.observe(Play::Rock, theirs);
+        }
+
+        // After rock they have always played paper, so play scissors.
+        assert_eq!(player.play(&mut Rng::new(0)), Play::Scissor);
+    }
+
+    #[test]
+    fn test_round_robin_counts_every_round() {
+        let mut players = players();
+        let standings = round_robin(&mut players, 100, 1, &Scoring::default());
+
+        for standing in &standings {
+            assert_eq!(standing.rounds(), 400);
+            assert_eq!(standing.scores.values().sum::<usize>(), 400);
+        }
+        let wins: usize = standings.iter().map(|s| s.wins).sum();
+        let losses: usize = standings.iter().map(|s| s.losses).sum();
+        assert_eq!(wins, losses);
+    }
+
+    #[test]
+    fn test_round_robin_replays_from_seed() {
+        let first = round_robin(&mut players(), 50, 9, &Scoring::default());
+        let again = round_robin(&mut players(), 50, 9, &Scoring::default());
+
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn test_markov_beats_a_repeating_guide() {
+        let mut players: Vec<Box<dyn Player>> = vec![
+            Box::new(Guide::new(vec![Play::Rock, Play::Paper, Play::Scissor])),
+            Box::new(Markov::default()),
+        ];
+        let standings = round_robin(&mut players, 300, 3, &Scoring::default());
+
+        assert_eq!(standings[1].win_rate() > 0.9, true);
+    }
+}
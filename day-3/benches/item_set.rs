@@ -0,0 +1,134 @@
+//! Compares finding common items with `ItemSet` against searching the strings directly, as
+//! day 3 used to, on generated rucksacks of growing size.
+//!
+//! Run with `cargo bench -p day-3`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use day_3::{get_item_score, Party, Rucksack};
+
+/// Small xorshift generator, so the inputs are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+
+    /// `len` letters picked from `letters`, followed by `extra`.
+    fn letters(&mut self, letters: &[u8], len: usize, extra: char) -> String {
+        let mut items: String =
+            (0..len - 1).map(|_| letters[self.below(letters.len())] as char).collect();
+        items.push(extra);
+        items
+    }
+}
+
+/// Rucksacks whose compartments share only `z`, found last in the first compartment, which is
+/// the worst case for searching.
+fn rucksacks(rng: &mut Rng, count: usize, size: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            rng.letters(b"abcdefghijklmnopqrstuvwxy", size, 'z')
+                + &rng.letters(b"ABCDEFGHIJKLMNOPQRSTUVWXY", size, 'z')
+        })
+        .collect()
+}
+
+/// Parties of three whose only item in common is `Z`.
+fn parties(rng: &mut Rng, count: usize, size: usize) -> Vec<[String; 3]> {
+    (0..count)
+        .map(|_| {
+            [b"abcdefghijklmnopq".as_slice(), b"rstuvwxyzABCDEFGH", b"IJKLMNOPQRSTUVWXY"]
+                .map(|letters| rng.letters(letters, 2 * size, 'Z'))
+        })
+        .collect()
+}
+
+fn naive_common_item(items: &str) -> Option<char> {
+    let (first, second) = items.split_at(items.len() / 2);
+    first.chars().find(|&c| second.contains(c))
+}
+
+fn naive_identity_item([a, b, c]: &[String; 3]) -> Option<char> {
+    a.chars().find(|&x| b.contains(x) && c.contains(x))
+}
+
+fn time<T>(f: impl Fn() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+fn report(what: &str, size: usize, count: usize, naive: Duration, sets: Duration) {
+    println!(
+        "{:<10} {:>6} {:>7} {:>12.3?} {:>12.3?} {:>9.1}x",
+        what,
+        size,
+        count,
+        naive,
+        sets,
+        naive.as_secs_f64() / sets.as_secs_f64()
+    );
+}
+
+fn main() {
+    let mut rng = Rng(0x2022_1203);
+
+    println!(
+        "{:<10} {:>6} {:>7} {:>12} {:>12} {:>10}",
+        "Search", "Size", "Count", "Strings", "ItemSet", "Speedup"
+    );
+
+    for (size, count) in [(16, 20_000), (256, 2_000), (4_096, 40)] {
+        let input = rucksacks(&mut rng, count, size);
+
+        let (naive_total, naive) = time(|| {
+            input
+                .iter()
+                .map(|line| get_item_score(&naive_common_item(line).unwrap()))
+                .sum::<u32>()
+        });
+        let (sets_total, sets) = time(|| {
+            input
+                .iter()
+                .map(|line| {
+                    get_item_score(&Rucksack::new(line.clone()).get_common_item().unwrap())
+                })
+                .sum::<u32>()
+        });
+
+        assert_eq!(naive_total, sets_total);
+        report("rucksack", size, count, naive, sets);
+    }
+
+    for (size, count) in [(16, 20_000), (256, 2_000), (4_096, 40)] {
+        let input = parties(&mut rng, count, size);
+
+        let (naive_total, naive) = time(|| {
+            input
+                .iter()
+                .map(|party| get_item_score(&naive_identity_item(party).unwrap()))
+                .sum::<u32>()
+        });
+        let (sets_total, sets) = time(|| {
+            input
+                .iter()
+                .map(|elves| {
+                    let mut party = Party::new();
+                    for elf in elves {
+                        party.add_elf(Rucksack::new(elf.clone())).unwrap();
+                    }
+                    get_item_score(&party.find_identity_item().unwrap())
+                })
+                .sum::<u32>()
+        });
+
+        assert_eq!(naive_total, sets_total);
+        report("party", size, count, naive, sets);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::get_item_score;
+
+/// A set of rucksack items, one bit per priority: bit 1 is `a` through to bit 52 for `Z`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+
+    /// Every item in `items`. Anything that is not a letter is left out.
+    pub fn from_items(items: &str) -> Self {
+        items.chars().fold(Self::EMPTY, |mut set, item| {
+            set.insert(item);
+            set
+        })
+    }
+
+    /// Add an item, returning false if it is not a letter.
+    pub fn insert(&mut self, item: char) -> bool {
+        if !item.is_ascii_alphabetic() {
+            return false;
+        }
+        self.0 |= 1 << get_item_score(&item);
+        true
+    }
+
+    pub fn contains(self, item: char) -> bool {
+        item.is_ascii_alphabetic() && self.0 & (1 << get_item_score(&item)) != 0
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    /// The items in this set that are not in `other`.
+    pub fn difference(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & !other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The item with the lowest priority, if there is one.
+    pub fn first(self) -> Option<char> {
+        self.iter().next()
+    }
+
+    /// The items in priority order.
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        (1..=52u8)
+            .filter(move |&priority| self.0 & (1 << priority) != 0)
+            .map(|priority| match priority {
+                1..=26 => (b'a' + priority - 1) as char,
+                _ => (b'A' + priority - 27) as char,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_from_items_ignores_repeats() {
+        let set = ItemSet::from_items("aAbaZ");
+
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.iter().collect::<String>(), "abAZ");
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = ItemSet::from_items("abcX");
+        let b = ItemSet::from_items("bcdY");
+
+        assert_eq!(a.union(b), ItemSet::from_items("abcdXY"));
+        assert_eq!(a.intersection(b), ItemSet::from_items("bc"));
+        assert_eq!(a.difference(b), ItemSet::from_items("aX"));
+        assert_eq!(a.difference(a).is_empty(), true);
+    }
+
+    #[test]
+    fn test_contains_and_first() {
+        let set = ItemSet::from_items("Qz");
+
+        assert_eq!(set.contains('z'), true);
+        assert_eq!(set.contains('Z'), false);
+        assert_eq!(set.first(), Some('z'));
+        assert_eq!(ItemSet::EMPTY.first(), None);
+    }
+
+    #[test]
+    fn test_insert_rejects_non_letters() {
+        let mut set = ItemSet::EMPTY;
+
+        assert_eq!(set.insert('1'), false);
+        assert_eq!(set.contains('1'), false);
+        assert_eq!(set.is_empty(), true);
+    }
+}
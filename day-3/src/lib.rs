@@ -0,0 +1,245 @@
+use common::{Error, Solution};
+
+mod item_set;
+
+pub use item_set::ItemSet;
+
+#[derive(Clone, Debug)]
+pub struct Rucksack {
+    total_contents: ItemSet,
+    containers: (ItemSet, ItemSet),
+}
+
+impl Rucksack {
+    pub fn new(total_contents: String) -> Self {
+        let split = total_contents.split_at(total_contents.len() / 2);
+        let content = (ItemSet::from_items(split.0), ItemSet::from_items(split.1));
+        Self {
+            total_contents: content.0.union(content.1),
+            containers: content,
+        }
+    }
+
+    /// Every item in the rucksack.
+    pub fn items(&self) -> ItemSet {
+        self.total_contents
+    }
+
+    /// The item found in both compartments. Should there be more than one, the one with the
+    /// lowest priority.
+    pub fn get_common_item(&self) -> Option<char> {
+        self.containers.0.intersection(self.containers.1).first()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Party {
+    elves: [Option<Rucksack>; 3],
+}
+
+impl Party {
+    pub fn new() -> Self {
+        let elves: [Option<Rucksack>; 3] = [None, None, None];
+        Self { elves }
+    }
+
+    pub fn add_elf(&mut self, elf: Rucksack) -> Result<&str, &str> {
+        for slot in self.elves.iter_mut() {
+            // Find the first element of the party which is None
+            if slot.is_none() {
+                *slot = Some(elf);
+                return Ok("Elf Added");
+            }
+        }
+        Err("Party already full!")
+    }
+
+    pub fn find_identity_item(self) -> Result<char, &'static str> {
+        if self.is_full() {
+            return self
+                .elves
+                .iter()
+                .map(|elf| elf.as_ref().unwrap().items())
+                .reduce(ItemSet::intersection)
+                .and_then(ItemSet::first)
+                .ok_or("Unable to find identity item");
+        }
+
+        Err(
+            "Cannot find identity item of incomplete party. Party must contain 3 elves.",
+        )
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.elves.iter().all(|x| x.is_some())
+    }
+}
+
+impl Default for Party {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn get_item_score(c: &char) -> u32 {
+    if c.is_uppercase() {
+        return *c as u32 - 38;
+    }
+    *c as u32 - 96
+}
+
+/// Parse one line of rucksack contents, which must be an even number of letters.
+pub fn parse_rucksack(line: &str) -> common::Result<Rucksack> {
+    if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(Error::parse(1, i + 1, c.to_string(), "items must be letters a-z or A-Z"));
+    }
+
+    if !line.len().is_multiple_of(2) {
+        return Err(Error::parse(
+            1,
+            1,
+            line,
+            "rucksack must hold the same number of items in each compartment",
+        ));
+    }
+
+    Ok(Rucksack::new(line.to_string()))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Rucksack>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> common::Result<Self::Input> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_rucksack(line).map_err(|e| e.at_line(i + 1)))
+            .collect()
+    }
+
+    /// Sum of the priorities of the item found in both compartments of each rucksack.
+    fn part1(rucksacks: &Self::Input) -> common::Result<Self::Answer> {
+        let mut total_score: u32 = 0;
+        for (i, rucksack) in rucksacks.iter().enumerate() {
+            let incorrect_item = rucksack.get_common_item().ok_or_else(|| {
+                Error::Semantic(format!("No common item found in rucksack {}", i + 1))
+            })?;
+            total_score += get_item_score(&incorrect_item);
+        }
+        Ok(total_score)
+    }
+
+    /// Sum of the priorities of each party's identity item.
+    fn part2(rucksacks: &Self::Input) -> common::Result<Self::Answer> {
+        let mut total_party_score: u32 = 0;
+        for (i, elves) in rucksacks.chunks(3).enumerate() {
+            let mut party = Party::new();
+            for elf in elves {
+                party
+                    .add_elf(elf.clone())
+                    .map_err(|e| Error::Semantic(format!("Party {}: {}", i + 1, e)))?;
+            }
+
+            let identity = party
+                .find_identity_item()
+                .map_err(|e| Error::Semantic(format!("Party {}: {}", i + 1, e)))?;
+            total_party_score += get_item_score(&identity);
+        }
+        Ok(total_party_score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_item_converts_to_correct_score() {
+        // Check the 4 boundaries as the input guarantees all characters are within these limits.
+        assert_eq!(get_item_score(&'a'), 1);
+        assert_eq!(get_item_score(&'z'), 26);
+        assert_eq!(get_item_score(&'A'), 27);
+        assert_eq!(get_item_score(&'Z'), 52);
+    }
+
+    #[test]
+    fn test_find_party_identity_item_success() {
+        let mut party = Party::new();
+        party
+            .add_elf(Rucksack::new(
+            "vJrwpWtwJgWrhcsFMMfFFhFp".to_string(),
+            ))
+            .expect("unexpected");
+        party
+            .add_elf(Rucksack::new(
+                "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string(),
+            ))
+            .expect("unexpected");
+        party
+            .add_elf(Rucksack::new("PmmdzqPrVvPwwTWBwg".to_string()))
+            .expect("unexpected");
+
+        dbg!(&party);
+
+        assert_eq!(party.find_identity_item().unwrap(), 'r')
+    }
+
+    #[test]
+    fn test_common_item_of_example_rucksack() {
+        let rucksack = Rucksack::new("vJrwpWtwJgWrhcsFMMfFFhFp".to_string());
+
+        assert_eq!(rucksack.get_common_item(), Some('p'));
+        assert_eq!(rucksack.items().len(), 14);
+    }
+
+    #[test]
+    fn test_find_party_errors_on_incomplete_party() {
+        let mut party = Party::new();
+        party
+            .add_elf(Rucksack::new(
+                "vJrwpWtwJgWrhcsFMMfFFhFp".to_string(),
+            ))
+            .expect("unexpected");
+        party
+            .add_elf(Rucksack::new(
+                "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string(),
+            ))
+            .expect("unexpected");
+        // Third party member is None
+
+        dbg!(&party);
+
+        assert_eq!(
+            party.find_identity_item(),
+            Err("Cannot find identity item of incomplete party. Party must contain 3 elves.")
+        )
+    }
+
+    #[test]
+    fn test_parse_rucksack_rejects_non_letters() {
+        assert_eq!(
+            parse_rucksack("abc1").err().unwrap().to_string(),
+            "line 1, column 4: items must be letters a-z or A-Z (found '1')"
+        )
+    }
+
+    #[test]
+    fn test_parse_rucksack_rejects_odd_item_count() {
+        assert_eq!(parse_rucksack("abc").is_err(), true)
+    }
+
+    #[test]
+    fn test_incomplete_last_party_is_an_error() {
+        let rucksacks = Day3::parse("vJrwpWtwJgWrhcsFMMfFFhFp\n").unwrap();
+
+        assert_eq!(
+            Day3::part2(&rucksacks).err().unwrap().to_string(),
+            "Party 1: Cannot find identity item of incomplete party. Party must contain 3 elves."
+        )
+    }
+}
@@ -0,0 +1,192 @@
+use common::{Error, Result, Solution};
+
+pub fn is_fully_contained(x: &[i32], y: &[i32]) -> bool {
+    if x.len() == 1 {
+        return y.contains(x.first().unwrap());
+    }
+
+    if y.len() == 1 {
+        return x.contains(y.first().unwrap());
+    }
+
+    // check if y inside x
+    if x.first().unwrap() <= y.first().unwrap() && x.last().unwrap() >= y.last().unwrap() {
+        return true;
+    // check if x inside y
+    } else if x.first().unwrap() >= y.first().unwrap() && x.last().unwrap() <= y.last().unwrap() {
+        return true;
+    }
+
+    false
+}
+
+pub fn is_partially_contained(x: &[i32], y: &[i32]) -> bool {
+    x.iter().any(|x| y.contains(x))
+}
+
+pub fn str_to_range(text: &str) -> Result<Vec<i32>> {
+    section_to_range(text, 1)
+}
+
+/// Parse a `<start>-<end>` section assignment that begins at `column` of its line.
+fn section_to_range(text: &str, column: usize) -> Result<Vec<i32>> {
+    let (start, end) = text
+        .split_once('-')
+        .ok_or_else(|| Error::parse(1, column, text, "expected a range like 2-4"))?;
+
+    let start: i32 = start
+        .parse()
+        .map_err(|_| Error::parse(1, column, start, "expected a section number"))?;
+    let end_column = column + text.len() - end.len();
+    let end: i32 = end
+        .parse()
+        .map_err(|_| Error::parse(1, end_column, end, "expected a section number"))?;
+
+    if start > end {
+        return Err(Error::parse(1, column, text, "range starts after it ends"));
+    }
+
+    Ok((start..=end).collect())
+}
+
+pub fn parse_line(line: &str) -> Result<(Vec<i32>, Vec<i32>)> {
+    let (first, second) = line
+        .split_once(',')
+        .ok_or_else(|| Error::parse(1, 1, line, "expected two comma separated ranges"))?;
+
+    Ok((
+        section_to_range(first, 1)?,
+        section_to_range(second, first.len() + 2)?,
+    ))
+}
+
+pub fn compute_line(line: String) -> Result<(bool, bool)> {
+    let ranges = parse_line(&line)?;
+
+    let full = is_fully_contained(
+        &ranges.0, &ranges.1
+    );
+
+    let partial = is_partially_contained(
+        &ranges.0, &ranges.1
+    );
+
+    Ok((full, partial))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<(Vec<i32>, Vec<i32>)>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_line(line).map_err(|e| e.at_line(i + 1)))
+            .collect()
+    }
+
+    /// Number of pairs where one assignment fully contains the other.
+    fn part1(pairs: &Self::Input) -> Result<Self::Answer> {
+        Ok(pairs
+            .iter()
+            .filter(|(x, y)| is_fully_contained(x, y))
+            .count())
+    }
+
+    /// Number of pairs where the assignments overlap at all.
+    fn part2(pairs: &Self::Input) -> Result<Self::Answer> {
+        Ok(pairs
+            .iter()
+            .filter(|(x, y)| is_partially_contained(x, y))
+            .count())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fist_vec_contains_second_vec() {
+        let vec1: Vec<i32> = (1..5).collect();
+        let vec2: Vec<i32> = (2..4).collect();
+
+        assert_eq!(is_fully_contained(&vec1, &vec2), true)
+    }
+
+    #[test]
+    fn test_second_vec_contains_first_vec() {
+        let vec1: Vec<i32> = (1..5).collect();
+        let vec2: Vec<i32> = (2..4).collect();
+
+        assert_eq!(is_fully_contained(&vec2, &vec1), true)
+    }
+
+    #[test]
+    fn test_vec_contains_itself() {
+        let vec: Vec<i32> = (1..5).collect();
+
+        assert_eq!(is_fully_contained(vec.clone().as_ref(), &vec), true)
+    }
+
+    #[test]
+    fn test_same_start_contains() {
+        let vec1: Vec<i32> = (1..5).collect();
+        let vec2: Vec<i32> = (1..4).collect();
+
+        assert_eq!(is_fully_contained(&vec1, &vec2), true)
+    }
+
+    #[test]
+    fn test_same_end_contains() {
+        let vec1: Vec<i32> = (1..5).collect();
+        let vec2: Vec<i32> = (2..5).collect();
+
+        assert_eq!(is_fully_contained(&vec1, &vec2), true)
+    }
+
+    #[test]
+    fn test_does_not_contain() {
+        let vec1: Vec<i32> = (1..5).collect();
+        let vec2: Vec<i32> = (2..6).collect();
+
+        assert_eq!(is_fully_contained(&vec1, &vec2), false)
+    }
+
+    #[test]
+    fn test_process_line_fully_contained() {
+        assert_eq!(compute_line("1-5,2-4".to_string()).unwrap(), (true, true))
+    }
+
+    #[test]
+    fn test_process_line_no_overlap() {
+        assert_eq!(compute_line("1-5,6-10".to_string()).unwrap(), (false, false))
+    }
+
+    #[test]
+    fn test_process_line_partially_contained() {
+        assert_eq!(compute_line("1-5,2-6".to_string()).unwrap(), (false, true))
+    }
+
+    #[test]
+    fn test_process_line_same_start_and_end_value() {
+        assert_eq!(compute_line("64-64,12-63".to_string()).unwrap(), (false, false))
+    }
+
+    #[test]
+    fn test_process_line_reports_bad_section_number() {
+        assert_eq!(
+            compute_line("1-5,2-x".to_string()).err().unwrap().to_string(),
+            "line 1, column 7: expected a section number (found 'x')"
+        )
+    }
+
+    #[test]
+    fn test_process_line_rejects_reversed_range() {
+        assert_eq!(compute_line("5-1,2-3".to_string()).is_err(), true)
+    }
+}
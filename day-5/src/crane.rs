@@ -0,0 +1,87 @@
+use crate::{Instruction, Stockpile, StockpileError};
+
+/// How a crane carries crates from one pile to another.
+pub trait CraneModel {
+    /// Model number, as given on the command line.
+    fn model(&self) -> &'static str;
+
+    fn apply(&self, stockpile: &mut Stockpile, instruction: &Instruction) -> Result<(), StockpileError>;
+}
+
+/// Lifts one crate at a time, so a moved stack lands in reverse order.
+pub struct CrateMover9000;
+
+impl CraneModel for CrateMover9000 {
+    fn model(&self) -> &'static str {
+        "9000"
+    }
+
+    fn apply(&self, stockpile: &mut Stockpile, instruction: &Instruction) -> Result<(), StockpileError> {
+        stockpile.move_crates(instruction.from, instruction.to, instruction.count)
+    }
+}
+
+/// Lifts the whole stack at once, keeping the crates in order.
+pub struct CrateMover9001;
+
+impl CraneModel for CrateMover9001 {
+    fn model(&self) -> &'static str {
+        "9001"
+    }
+
+    fn apply(&self, stockpile: &mut Stockpile, instruction: &Instruction) -> Result<(), StockpileError> {
+        stockpile.move_stack(instruction.from, instruction.to, instruction.count)
+    }
+}
+
+/// Every crane the supply ship has been known to carry.
+pub const CRANE_MODELS: [&dyn CraneModel; 2] = [&CrateMover9000, &CrateMover9001];
+
+/// Look up a crane by its model number.
+pub fn crane_model(model: &str) -> Option<&'static dyn CraneModel> {
+    CRANE_MODELS.iter().copied().find(|crane| crane.model() == model)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn stockpile() -> Stockpile {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "[C]".to_string(),
+            "[B]".to_string(),
+            "[A]".to_string(),
+            " 1   2".to_string(),
+        ];
+
+        stockpile.initiate(start_state).unwrap();
+        stockpile
+    }
+
+    #[test]
+    fn test_crate_mover_9000_reverses_moved_crates() {
+        let mut stockpile = stockpile();
+
+        stockpile.apply(&Instruction::new(2, 0, 1), &CrateMover9000).unwrap();
+
+        assert_eq!(stockpile.get_top_item_lables(), vec!['A', 'B']);
+    }
+
+    #[test]
+    fn test_crate_mover_9001_keeps_moved_crates_in_order() {
+        let mut stockpile = stockpile();
+
+        stockpile.apply(&Instruction::new(2, 0, 1), &CrateMover9001).unwrap();
+
+        assert_eq!(stockpile.get_top_item_lables(), vec!['A', 'C']);
+    }
+
+    #[test]
+    fn test_crane_model_lookup() {
+        assert_eq!(crane_model("9001").unwrap().model(), "9001");
+        assert_eq!(crane_model("9002").is_none(), true);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::Pile;
+
+/// A single recorded move between two piles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Crates lifted one at a time, so they land in reverse order.
+    Crates { from: usize, to: usize, count: usize },
+    /// A stack lifted all at once, so it keeps its order.
+    Stack { from: usize, to: usize, count: usize },
+}
+
+impl Operation {
+    /// The operation that puts the crates back where they came from.
+    pub fn inverse(&self) -> Self {
+        match *self {
+            Operation::Crates { from, to, count } => Operation::Crates { from: to, to: from, count },
+            Operation::Stack { from, to, count } => Operation::Stack { from: to, to: from, count },
+        }
+    }
+}
+
+/// The log of operations applied to a stockpile since it was drawn. Operations after the
+/// current step have been undone and can be redone until something new is recorded.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    origin: Vec<Pile>,
+    operations: Vec<Operation>,
+    step: usize,
+}
+
+impl History {
+    pub fn new(origin: Vec<Pile>) -> Self {
+        Self {
+            origin,
+            operations: vec![],
+            step: 0,
+        }
+    }
+
+    /// The piles as they were before the first operation.
+    pub fn origin(&self) -> &[Pile] {
+        &self.origin
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// How many operations are currently applied.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Record a new operation, dropping any that were undone.
+    pub fn record(&mut self, operation: Operation) {
+        self.operations.truncate(self.step);
+        self.operations.push(operation);
+        self.step += 1;
+    }
+
+    /// Step back, returning the operation that needs reversing.
+    pub fn undo(&mut self) -> Option<Operation> {
+        self.step = self.step.checked_sub(1)?;
+        Some(self.operations[self.step])
+    }
+
+    /// Step forward, returning the operation that needs applying again.
+    pub fn redo(&mut self) -> Option<Operation> {
+        let operation = *self.operations.get(self.step)?;
+        self.step += 1;
+        Some(operation)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_inverse_swaps_piles() {
+        assert_eq!(
+            Operation::Crates { from: 0, to: 2, count: 3 }.inverse(),
+            Operation::Crates { from: 2, to: 0, count: 3 }
+        );
+        assert_eq!(
+            Operation::Stack { from: 1, to: 0, count: 2 }.inverse(),
+            Operation::Stack { from: 0, to: 1, count: 2 }
+        );
+    }
+
+    #[test]
+    fn test_record_drops_undone_operations() {
+        let mut history = History::new(vec![]);
+        let first = Operation::Stack { from: 0, to: 1, count: 1 };
+        let second = Operation::Stack { from: 1, to: 0, count: 1 };
+
+        history.record(first);
+        history.record(first);
+        assert_eq!(history.undo(), Some(first));
+        history.record(second);
+
+        assert_eq!(history.operations(), &[first, second]);
+        assert_eq!(history.step(), 2);
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn test_undo_stops_at_origin() {
+        let mut history = History::new(vec![]);
+
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.step(), 0);
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+use std::str::FromStr;
+
+use common::{tokens, Error};
+
+/// A single step of the rearrangement procedure, `move <count> from <from> to <to>`.
+///
+/// Piles are numbered from one in the procedure but are held zero indexed here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Instruction {
+    pub fn new(count: usize, from: usize, to: usize) -> Self {
+        Self { count, from, to }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    /// Parse one line of the procedure. Errors point at the offending token on line 1, use
+    /// [`Error::at_line`] to place them in a larger input.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = tokens(line);
+        let end = line.len() + 1;
+        let mut numbers = [0; 3];
+
+        for (keyword, number) in ["move", "from", "to"].iter().zip(numbers.iter_mut()) {
+            match words.next() {
+                Some((_, word)) if word == *keyword => {}
+                Some((column, word)) => {
+                    return Err(Error::parse(1, column, word, format!("expected '{}'", keyword)))
+                }
+                None => return Err(Error::parse(1, end, "", format!("expected '{}'", keyword))),
+            }
+
+            let (column, word) = words.next().ok_or_else(|| {
+                Error::parse(1, end, "", format!("expected a number after '{}'", keyword))
+            })?;
+            *number = word
+                .parse::<usize>()
+                .map_err(|_| Error::parse(1, column, word, "expected a number"))?;
+
+            if *number == 0 && *keyword != "move" {
+                return Err(Error::parse(1, column, word, "piles are numbered from 1"));
+            }
+        }
+
+        if let Some((column, word)) = words.next() {
+            return Err(Error::parse(1, column, word, "unexpected text after instruction"));
+        }
+
+        let [count, from, to] = numbers;
+        Ok(Instruction::new(count, from - 1, to - 1))
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from + 1, self.to + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_instruction_parses_zero_indexed_piles() {
+        assert_eq!(
+            Instruction::from_str("move 3 from 1 to 12").unwrap(),
+            Instruction::new(3, 0, 11)
+        );
+    }
+
+    #[test]
+    fn test_instruction_round_trips_through_display() {
+        let line = "move 11 from 9 to 2";
+
+        assert_eq!(Instruction::from_str(line).unwrap().to_string(), line);
+    }
+
+    #[test]
+    fn test_instruction_reports_misspelt_keyword() {
+        assert_eq!(
+            Instruction::from_str("move 2 form 2 to 1").err().unwrap().to_string(),
+            "line 1, column 8: expected 'from' (found 'form')"
+        );
+    }
+
+    #[test]
+    fn test_instruction_reports_bad_number() {
+        assert_eq!(
+            Instruction::from_str("move 2 from two to 1").err().unwrap().to_string(),
+            "line 1, column 13: expected a number (found 'two')"
+        );
+    }
+
+    #[test]
+    fn test_instruction_reports_missing_token() {
+        assert_eq!(
+            Instruction::from_str("move 2 from 1").err().unwrap().to_string(),
+            "line 1, column 14: expected 'to' (found '')"
+        );
+    }
+
+    #[test]
+    fn test_instruction_rejects_pile_zero() {
+        assert_eq!(Instruction::from_str("move 1 from 0 to 1").is_err(), true);
+    }
+
+    #[test]
+    fn test_instruction_rejects_trailing_text() {
+        assert_eq!(Instruction::from_str("move 1 from 2 to 1 now").is_err(), true);
+    }
+}
@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use common::{Error, Result, Solution};
+
+mod crane;
+mod history;
+mod instruction;
+mod stockpile;
+mod trace;
+
+pub use crane::{crane_model, CraneModel, CrateMover9000, CrateMover9001, CRANE_MODELS};
+pub use history::{History, Operation};
+pub use instruction::Instruction;
+pub use stockpile::{Item, Pile, Stockpile, StockpileError, Underflow};
+pub use trace::{Frame, Frames};
+
+/// The starting drawing of the stockpile and the instructions to apply to it.
+#[derive(Clone, Debug)]
+pub struct Procedure {
+    pub stockpile: Stockpile,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Procedure {
+    /// Carry out every instruction on a copy of the stockpile with the given crane, and return
+    /// the labels on top of each pile.
+    pub fn rearrange(&self, crane: &dyn CraneModel) -> Result<String> {
+        let mut stockpile = self.stockpile.clone();
+
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            stockpile
+                .apply(instruction, crane)
+                .map_err(|e| failed_step(i + 1, instruction, e))?;
+        }
+
+        Ok(stockpile.get_top_item_lables().into_iter().collect())
+    }
+
+    /// Step through the procedure with the given crane, a frame at a time.
+    pub fn frames<'a>(&'a self, crane: &'a dyn CraneModel) -> Frames<'a> {
+        Frames::new(self, crane)
+    }
+}
+
+/// Describe an instruction the crane could not carry out.
+fn failed_step(step: usize, instruction: &Instruction, e: StockpileError) -> Error {
+    Error::Semantic(format!("step {} ({}): {}", step, instruction, e))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Procedure;
+    type Answer = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut stockpile: Stockpile = Stockpile::new(vec![]);
+        let mut instructions = vec![];
+
+        let mut start_state: Vec<String> = vec![];
+        let mut process_rules = false;
+
+        for (i, line) in input.lines().enumerate() {
+            if process_rules {
+                let instruction = Instruction::from_str(line).map_err(|e| e.at_line(i + 1))?;
+
+                // Reject the whole procedure before any crate is moved.
+                stockpile
+                    .validate(&instruction)
+                    .map_err(|e| Error::Semantic(format!("line {}: {}", i + 1, e)))?;
+
+                instructions.push(instruction);
+            } else if line.is_empty() {
+                stockpile.initiate(start_state.clone())?;
+                process_rules = true;
+            } else {
+                start_state.push(line.to_string());
+            }
+        }
+
+        if !process_rules {
+            return Err(Error::Semantic(
+                "Drawing of the stockpile must be followed by a blank line".to_string(),
+            ));
+        }
+
+        Ok(Procedure { stockpile, instructions })
+    }
+
+    /// Top crates after moving them one at a time.
+    fn part1(procedure: &Self::Input) -> Result<Self::Answer> {
+        procedure.rearrange(&CrateMover9000)
+    }
+
+    /// Top crates after moving whole stacks at once.
+    fn part2(procedure: &Self::Input) -> Result<Self::Answer> {
+        procedure.rearrange(&CrateMover9001)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const EXAMPLE: &str = "    [D]    \n\
+                           [N] [C]    \n\
+                           [Z] [M] [P]\n \
+                           1   2   3\n\
+                           \n\
+                           move 1 from 2 to 1\n\
+                           move 3 from 1 to 3\n\
+                           move 2 from 2 to 1\n\
+                           move 1 from 1 to 2\n";
+
+    #[test]
+    fn test_solution_answers_example() {
+        let procedure = Day5::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day5::part1(&procedure).unwrap(), "CMZ");
+        assert_eq!(Day5::part2(&procedure).unwrap(), "MCD");
+    }
+    #[test]
+    fn test_parse_reports_bad_move() {
+        let input = EXAMPLE.replace("move 2 from 2 to 1", "move 2 form 2 to 1");
+
+        assert_eq!(
+            Day5::parse(&input).err().unwrap().to_string(),
+            "line 8, column 8: expected 'from' (found 'form')"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_pile_zero() {
+        let input = EXAMPLE.replace("move 1 from 2 to 1", "move 1 from 0 to 1");
+
+        assert_eq!(Day5::parse(&input).is_err(), true);
+    }
+
+    #[test]
+    fn test_parse_rejects_move_to_missing_pile() {
+        let input = EXAMPLE.replace("move 1 from 1 to 2", "move 1 from 1 to 4");
+
+        assert_eq!(
+            Day5::parse(&input).err().unwrap().to_string(),
+            "line 9: pile 4 does not exist, the stockpile has 3 piles"
+        );
+    }
+
+    #[test]
+    fn test_rearrange_reports_step_that_underflows() {
+        let input = EXAMPLE.replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        let procedure = Day5::parse(&input).unwrap();
+
+        assert_eq!(
+            Day5::part1(&procedure).err().unwrap().to_string(),
+            "step 2 (move 4 from 1 to 3): cannot move 4 crates from pile 1, it only holds 3"
+        );
+    }
+}
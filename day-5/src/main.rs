@@ -1,198 +1,167 @@
-use common::read_lines;
-use std::{env, usize};
-use std::str::FromStr;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::process::exit;
 
-#[derive(Clone, Debug)]
-struct Stockpile {
-    piles: Vec<Pile>
-}
-
-impl Stockpile {
-
-    pub fn new(piles: Vec<Pile>) -> Self {
-        Self { piles }
-    }
-
-    pub fn piles(&self) -> &Vec<Pile> {
-        &self.piles
-    }
+use common::{Error, Solution};
+use day_5::{crane_model, CraneModel, Day5, Procedure, CRANE_MODELS};
 
-    pub fn get_pile(&self, i: usize) -> Option<&Pile> {
-        self.piles.get(i)
-    }
+const USAGE: &str = "\
+Usage: day-5 [--crane <9000|9001>] [--trace] [--trace-every <n>] [--frames <file>] <input>
 
-    pub fn get_pile_len(&self, i: usize) -> Option<usize> {
-        self.piles.get(i)
-            .and_then(|pile| Some(pile.items().len()))
-    }
+Prints the crates left on top of each pile for every crane model, or only for the
+model given with --crane.
 
-    pub fn add_item(&mut self, item: Item, pile: usize) {
-        if let Some(stack) = self.piles.get_mut(pile) {
-            stack.add_item(item.clone());
-        }
-    }
+  --trace            draw the stockpile after every instruction
+  --trace-every <n>  draw the stockpile after every n instructions, and at the end
+  --frames <file>    write the drawing after every instruction to a file";
 
-    pub fn add_items(&mut self, items: Vec<Item>, pile: usize) {
-        if let Some(stack) = self.piles.get_mut(pile) {
-            stack.add_items(items);
-        }
-    }
-
-    pub fn initiate(&mut self, mut state: Vec<String>) {
-
-        // this is the indexes of each stack. We need this to know how many stacks there are.
-        let n = usize::from_str(
-            state.pop().unwrap()
-                .chars().last().unwrap()
-                .to_string().as_str()
-        ).unwrap();
-
-        self.piles = vec![Pile::new(vec![]); n];
-
-        while let Some(line) = state.pop() {
-            self.process_line(line.chars().collect());
-        }
-    }
-
-    fn process_line(&mut self, data: Vec<char>) {
-        let n = data.len();
-        let mut pile = 0;
+struct Options {
+    crane: Option<&'static dyn CraneModel>,
+    trace_every: Option<usize>,
+    frames: Option<String>,
+    input: String,
+}
 
-        for i in (1..n).step_by(4) {
-            if let Some(c) = &data.get(i) {
-                if c.is_alphanumeric() {
-                    self.add_item(Item::new(c.to_owned().clone()), pile);
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let mut crane = None;
+    let mut trace_every = None;
+    let mut frames = None;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--crane" => {
+                let model = args.next().ok_or("Missing value for --crane")?;
+                crane = Some(crane_model(model).ok_or(format!("Unknown crane model '{}'", model))?);
+            }
+            "--trace" => trace_every = trace_every.or(Some(1)),
+            "--trace-every" => {
+                let every = args.next().ok_or("Missing value for --trace-every")?;
+                match every.parse::<usize>() {
+                    Ok(every) if every > 0 => trace_every = Some(every),
+                    _ => return Err(format!("Invalid value '{}' for --trace-every", every)),
                 }
             }
-            pile += 1;
+            "--frames" => frames = Some(args.next().ok_or("Missing value for --frames")?.to_string()),
+            flag if flag.starts_with("--") => return Err(format!("Unknown flag '{}'", flag)),
+            path => input = Some(path.to_string()),
         }
     }
 
-    // Assumption: All moves are "valid" (will never try remove from empty pile)
-    pub fn move_crate(&mut self, from: usize, to: usize) {
-        if let Some(from_pile) = self.piles.get_mut(from) {
-            if let Some(item) = from_pile.remove_item() {
-                self.add_item(item.clone(), to);
-            }
-        }
-    }
+    Ok(Options {
+        crane,
+        trace_every,
+        frames,
+        input: input.ok_or("Missing input file")?,
+    })
+}
 
-    pub fn move_stack(&mut self, from: usize, to: usize, count: usize) {
-        if let Some(from_pile) = self.piles.get_mut(from) {
-            if let Some(items) = from_pile.remove_items(count) {
-                dbg!(&items);
-                self.add_items(items, to);
+/// Rearrange the stockpile a frame at a time, drawing it to stdout every `trace_every` steps
+/// and writing every frame to `frames`, given with the path it writes to. Returns the labels on
+/// top of each pile.
+fn rearrange_traced(
+    procedure: &Procedure,
+    crane: &dyn CraneModel,
+    trace_every: Option<usize>,
+    mut frames: Option<(&str, &mut dyn Write)>,
+) -> common::Result<String> {
+    let last = procedure.instructions.len();
+    let mut steps = procedure.frames(crane);
+
+    let write_error = |path: &str, e: io::Error| {
+        Error::Semantic(format!("unable to write frames to {}: {}", path, e))
+    };
+
+    if let Some((path, out)) = frames.as_mut() {
+        writeln!(out, "CrateMover {}\n", crane.model()).map_err(|e| write_error(path, e))?;
+    }
+
+    for frame in steps.by_ref() {
+        let frame = frame?;
+        if let Some(every) = trace_every {
+            if frame.step % every == 0 || frame.step == last {
+                println!("{}", frame);
             }
         }
-
-    }
-
-    pub fn get_top_item_lables(self) -> Vec<char> {
-        let mut out: Vec<char> = Vec::new();
-        for pile in self.piles() {
-            if let Some(item) = pile.items().last() {
-                out.push(item.label.clone());
-            }
+        if let Some((path, out)) = frames.as_mut() {
+            writeln!(out, "{}", frame).map_err(|e| write_error(path, e))?;
         }
-        out
     }
 
+    Ok(steps.stockpile().clone().get_top_item_lables().into_iter().collect())
 }
 
-#[derive(Clone, Debug)]
-struct Pile {
-    items: Vec<Item>
-}
-
-impl Pile {
-    pub fn new(items: Vec<Item>) -> Self {
-        Self { items }
-    }
-
-    pub fn items(&self) -> &Vec<Item> {
-        &self.items
-    }
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    pub fn add_item(&mut self, item: Item) {
-        self.items.push(item.clone());
-    }
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            exit(2)
+        }
+    };
 
-    pub fn remove_item(&mut self) -> Option<Item> {
-        self.items.pop()
-    }
+    let file_path = &options.input;
+    println!("In file {}", file_path);
 
-    pub fn add_items(&mut self, mut items: Vec<Item>) {
-        while let Some(item) = items.pop() {
-            self.add_item(item);
+    let procedure = match fs::read_to_string(file_path)
+        .map_err(Error::from)
+        .and_then(|input| Day5::parse(&input))
+    {
+        Ok(procedure) => procedure,
+        Err(e) => {
+            eprintln!("error: {}: {}", file_path, e);
+            exit(1)
         }
-    }
-
-    pub fn remove_items(&mut self, count: usize) -> Option<Vec<Item>> {
-        let mut items: Vec<Item> = Vec::new();
-        for _ in 0..count {
-            items.push(self.items.pop().unwrap().clone());
+    };
+
+    let cranes = match options.crane {
+        Some(crane) => vec![crane],
+        None => CRANE_MODELS.to_vec(),
+    };
+
+    let mut frames = match &options.frames {
+        Some(path) => match File::create(path) {
+            Ok(file) => Some(BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("error: {}: {}", path, e);
+                exit(1)
+            }
+        },
+        None => None,
+    };
+
+    let mut failed = false;
+    for crane in cranes {
+        let top = if options.trace_every.is_some() || frames.is_some() {
+            let path = options.frames.as_deref().unwrap_or_default();
+            let out = frames.as_mut().map(|out| (path, out as &mut dyn Write));
+            rearrange_traced(&procedure, crane, options.trace_every, out)
+        } else {
+            procedure.rearrange(crane)
+        };
+
+        match top {
+            Ok(top) => println!("CrateMover {}: {}", crane.model(), top),
+            Err(e) => {
+                eprintln!("error: CrateMover {}: {}", crane.model(), e);
+                failed = true;
+            }
         }
-
-        Some(items)
     }
-}
 
-
-#[derive(Clone, Debug)]
-struct Item {
-    label: char,
-}
-
-impl Item {
-    pub fn new(label: char) -> Self {
-        Self { label }
+    if let Some(Err(e)) = frames.as_mut().map(|out| out.flush()) {
+        let path = options.frames.as_deref().unwrap_or_default();
+        eprintln!("error: unable to write frames to {}: {}", path, e);
+        exit(1)
     }
-}
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
 
-    let file_path = &args[1];
-    println!("In file {}", file_path);
-
-    let mut stockpile: Stockpile = Stockpile::new(vec![]);
-
-    let mut start_state: Vec<String> = vec![];
-    let mut process_rules = false;
-
-    if let Ok(lines) = read_lines(file_path) {
-        for l in lines {
-            if let Some(line) = l.ok() {
-                if process_rules {
-                    // move <count> from <from> to <to>
-                    let mut words = line.split_whitespace();
-                    let _ = words.next(); // move
-                    let count = words.next().unwrap().parse::<usize>().unwrap();
-                    let _ = words.next(); // from
-                    let from = words.next().unwrap().parse::<usize>().unwrap() - 1; //zero index
-                    let _ = words.next(); // to
-                    let to = words.next().unwrap().parse::<usize>().unwrap() - 1; //zero index
-                    // for _ in 0..count {
-                    //     stockpile.move_crate(from, to);
-                    // }
-                    stockpile.move_stack(from, to, count);
-
-                } else {
-                    if line == "" {
-                        dbg!(&start_state);
-                        stockpile.initiate(start_state.clone());
-                        process_rules = true;
-                        dbg!(&stockpile);
-                    } else {
-                        start_state.push(line);
-                    }
-                }
-            }
-        }
+    if failed {
+        exit(1)
     }
-
-    let top_string: String = stockpile.get_top_item_lables().into_iter().collect();
-    println!("{}", top_string);
 }
 
 #[cfg(test)]
@@ -200,136 +169,86 @@ mod test {
     use super::*;
     use pretty_assertions::assert_eq;
 
-    #[test]
-    fn test_stockpile_initiate_one_crate_every_row() {
-        let mut stockpile = Stockpile::new(vec![]);
-
-        let mut start_state: Vec<String> = vec![];
-        start_state.push("[A] [B] [C]".to_string());
-        start_state.push(" 1   2   3".to_string());
-
-        stockpile.initiate(start_state);
-
-        dbg!(&stockpile);
-        assert_eq!(stockpile.piles.len(), 3);
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
     }
 
     #[test]
-    fn test_stockpile_initiate_only_adds_to_correct_piles() {
-        let mut stockpile = Stockpile::new(vec![]);
-
-        let mut start_state: Vec<String> = vec![];
-        start_state.push("[D]     [E]".to_string());
-        start_state.push("[A] [B] [C]".to_string());
-        start_state.push(" 1   2   3".to_string());
-
-        stockpile.initiate(start_state);
-
-        dbg!(&stockpile);
-
-        assert_eq!(stockpile.get_pile_len(0).unwrap(), 2);
-        assert_eq!(stockpile.get_pile_len(1).unwrap(), 1);
-        assert_eq!(stockpile.get_pile_len(2).unwrap(), 2);
+    fn test_parse_args_defaults_to_every_crane() {
+        let options = parse_args(&args("input.txt")).unwrap();
 
+        assert_eq!(options.crane.is_none(), true);
+        assert_eq!(options.input, "input.txt");
     }
 
     #[test]
-    fn test_stockpile_initiate_only_adds_to_correct_pile_only_one_pile_full() {
-        let mut stockpile = Stockpile::new(vec![]);
-
-        let mut start_state: Vec<String> = vec![];
-        start_state.push("        [E]".to_string());
-        start_state.push("        [D]".to_string());
-        start_state.push("        [C]".to_string());
-        start_state.push("        [B]".to_string());
-        start_state.push("        [A]".to_string());
-        start_state.push(" 1   2   3".to_string());
-
-        stockpile.initiate(start_state);
-
-        dbg!(&stockpile);
+    fn test_parse_args_selects_crane() {
+        let options = parse_args(&args("--crane 9000 input.txt")).unwrap();
 
-        assert_eq!(stockpile.get_pile_len(0).unwrap(), 0);
-        assert_eq!(stockpile.get_pile_len(1).unwrap(), 0);
-        assert_eq!(stockpile.get_pile_len(2).unwrap(), 5);
+        assert_eq!(options.crane.unwrap().model(), "9000");
     }
 
     #[test]
-    fn test_stockpile_move_single_crate_from_one_pile_to_another() {
-        let mut stockpile = Stockpile::new(vec![]);
-
-        let mut start_state: Vec<String> = vec![];
-        start_state.push("        [A]".to_string());
-        start_state.push(" 1   2   3".to_string());
-
-        stockpile.initiate(start_state);
-
-        dbg!(&stockpile);
-
-        stockpile.move_crate(2, 0);
-
-        dbg!(&stockpile);
-
-        assert_eq!(stockpile.get_pile_len(0).unwrap(), 1);
-        assert_eq!(stockpile.get_pile_len(1).unwrap(), 0);
-        assert_eq!(stockpile.get_pile_len(2).unwrap(), 0);
+    fn test_parse_args_rejects_unknown_crane() {
+        assert_eq!(parse_args(&args("--crane 42 input.txt")).is_err(), true);
     }
 
     #[test]
-    fn test_stockpile_move_from_one_pile_to_another_inverts_pile_order() {
-        let mut stockpile = Stockpile::new(vec![]);
-
-        let mut start_state: Vec<String> = vec![];
-        start_state.push("[B]        ".to_string());
-        start_state.push("[A]        ".to_string());
-        start_state.push(" 1   2   3".to_string());
-
-        stockpile.initiate(start_state);
-
-        assert_eq!(stockpile.get_pile(0).unwrap().items().first().unwrap().label, 'A');
-        assert_eq!(stockpile.get_pile(0).unwrap().items().last().unwrap().label, 'B');
-
-        dbg!(&stockpile);
-
-        stockpile.move_crate(0, 1);
-        stockpile.move_crate(0, 1);
+    fn test_parse_args_trace_every_instruction() {
+        let options = parse_args(&args("--trace input.txt")).unwrap();
 
-        dbg!(&stockpile);
+        assert_eq!(options.trace_every, Some(1));
+    }
 
-        assert_eq!(stockpile.get_pile_len(0).unwrap(), 0);
-        assert_eq!(stockpile.get_pile_len(1).unwrap(), 2);
-        assert_eq!(stockpile.get_pile_len(2).unwrap(), 0);
+    #[test]
+    fn test_parse_args_trace_every_n_instructions() {
+        let options = parse_args(&args("--trace --trace-every 10 --frames out.txt input.txt")).unwrap();
 
-        assert_eq!(stockpile.get_pile(1).unwrap().items().first().unwrap().label, 'B');
-        assert_eq!(stockpile.get_pile(1).unwrap().items().last().unwrap().label, 'A');
+        assert_eq!(options.trace_every, Some(10));
+        assert_eq!(options.frames, Some("out.txt".to_string()));
     }
 
     #[test]
-    fn test_stockpile_move_stack_preserves_order() {
-        let mut stockpile = Stockpile::new(vec![]);
+    fn test_parse_args_rejects_zero_trace_interval() {
+        assert_eq!(parse_args(&args("--trace-every 0 input.txt")).is_err(), true);
+    }
 
-        let mut start_state: Vec<String> = vec![];
-        start_state.push("[B]        ".to_string());
-        start_state.push("[A]        ".to_string());
-        start_state.push(" 1   2   3".to_string());
+    #[test]
+    fn test_rearrange_traced_writes_every_frame() {
+        let procedure = Day5::parse("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\n").unwrap();
+        let mut out: Vec<u8> = vec![];
 
-        stockpile.initiate(start_state);
+        let top = rearrange_traced(&procedure, CRANE_MODELS[1], None, Some(("out.txt", &mut out))).unwrap();
 
-        assert_eq!(stockpile.get_pile(0).unwrap().items().first().unwrap().label, 'A');
-        assert_eq!(stockpile.get_pile(0).unwrap().items().last().unwrap().label, 'B');
+        assert_eq!(top, "A");
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "CrateMover 9001\n\n\
+             Step 0: start\n[A] [B]\n 1   2 \n\n\
+             Step 1: move 1 from 1 to 2\n    [A]\n    [B]\n 1   2 \n\n"
+        );
+    }
 
-        dbg!(&stockpile);
+    #[test]
+    fn test_rearrange_traced_reports_failed_writes() {
+        struct Full;
 
-        stockpile.move_stack(0, 1, 2);
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk full"))
+            }
 
-        dbg!(&stockpile);
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
 
-        assert_eq!(stockpile.get_pile_len(0).unwrap(), 0);
-        assert_eq!(stockpile.get_pile_len(1).unwrap(), 2);
-        assert_eq!(stockpile.get_pile_len(2).unwrap(), 0);
+        let procedure = Day5::parse("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\n").unwrap();
+        let error =
+            rearrange_traced(&procedure, CRANE_MODELS[0], None, Some(("out.txt", &mut Full)))
+                .err()
+                .unwrap();
 
-        assert_eq!(stockpile.get_pile(1).unwrap().items().first().unwrap().label, 'A');
-        assert_eq!(stockpile.get_pile(1).unwrap().items().last().unwrap().label, 'B');
+        assert_eq!(error.to_string(), "unable to write frames to out.txt: disk full");
     }
-
-}
\ No newline at end of file
+}
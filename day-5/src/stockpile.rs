@@ -0,0 +1,870 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use common::{tokens, Error};
+
+use crate::{CraneModel, History, Instruction, Operation};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StockpileError {
+    /// An instruction refers to a pile that is not in the drawing.
+    NoSuchPile { index: usize, piles: usize },
+    /// A move asks for more crates than the pile holds.
+    NotEnoughCrates { index: usize, requested: usize, available: usize },
+}
+
+impl fmt::Display for StockpileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StockpileError::NoSuchPile { index, piles } => write!(
+                f,
+                "pile {} does not exist, the stockpile has {} piles",
+                index + 1,
+                piles
+            ),
+            StockpileError::NotEnoughCrates { index, requested, available } => write!(
+                f,
+                "cannot move {} crates from pile {}, it only holds {}",
+                requested,
+                index + 1,
+                available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StockpileError {}
+
+impl From<StockpileError> for Error {
+    fn from(e: StockpileError) -> Self {
+        Error::Semantic(e.to_string())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Stockpile {
+    piles: Vec<Pile>,
+    history: History,
+}
+
+impl Stockpile {
+
+    pub fn new(piles: Vec<Pile>) -> Self {
+        let history = History::new(piles.clone());
+        Self { piles, history }
+    }
+
+    pub fn piles(&self) -> &Vec<Pile> {
+        &self.piles
+    }
+
+    pub fn get_pile(&self, i: usize) -> Option<&Pile> {
+        self.piles.get(i)
+    }
+
+    pub fn get_pile_len(&self, i: usize) -> Option<usize> {
+        self.piles.get(i)
+            .map(|pile| pile.items().len())
+    }
+
+    /// Check that an instruction only refers to piles in this stockpile.
+    pub fn validate(&self, instruction: &Instruction) -> Result<(), StockpileError> {
+        for index in [instruction.from, instruction.to] {
+            if index >= self.piles.len() {
+                return Err(StockpileError::NoSuchPile { index, piles: self.piles.len() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Only for building the piles and carrying out operations, which keep the history in step.
+    fn add_item(&mut self, item: Item, pile: usize) {
+        if let Some(stack) = self.piles.get_mut(pile) {
+            stack.add_item(item);
+        }
+    }
+
+    fn add_items(&mut self, items: Vec<Item>, pile: usize) {
+        if let Some(stack) = self.piles.get_mut(pile) {
+            stack.add_items(items);
+        }
+    }
+
+    pub fn initiate(&mut self, mut state: Vec<String>) -> common::Result<()> {
+        let index_line = state.len();
+
+        // this is the indexes of each stack. We need this to know how many stacks there are,
+        // and which columns of the rows above belong to each stack.
+        let indexes = state.pop()
+            .ok_or_else(|| Error::Semantic("Drawing of the stockpile is empty".to_string()))?;
+        let columns = pile_columns(&indexes).map_err(|e| e.at_line(index_line))?;
+
+        self.piles = vec![Pile::new(vec![]); columns.len()];
+
+        while let Some(line) = state.pop() {
+            let line_number = state.len() + 1;
+            self.process_line(&line, &columns).map_err(|e| e.at_line(line_number))?;
+        }
+
+        self.history = History::new(self.piles.clone());
+        Ok(())
+    }
+
+    /// Add the crates drawn on one row to the piles whose numbers sit beneath them. Rows may be
+    /// trimmed or stop short of the last pile.
+    fn process_line(&mut self, line: &str, columns: &[Range<usize>]) -> common::Result<()> {
+        let data = line.as_bytes();
+        let mut i = 0;
+
+        while i < data.len() {
+            if data[i].is_ascii_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            let text = line[i..].split_whitespace().next().unwrap_or_default();
+
+            let is_crate = data[i] == b'['
+                && data.get(i + 2) == Some(&b']')
+                && data[i + 1].is_ascii_alphanumeric();
+            if !is_crate {
+                return Err(Error::parse(1, i + 1, text, "expected a crate like [A]"));
+            }
+
+            let pile = pile_under(columns, i + 1)
+                .ok_or_else(|| Error::parse(1, i + 1, text, "crate is not above a pile number"))?;
+            self.add_item(Item::new(data[i + 1] as char), pile);
+            i += 3;
+        }
+
+        Ok(())
+    }
+
+    /// Carry out an instruction with the given crane.
+    pub fn apply(&mut self, instruction: &Instruction, crane: &dyn CraneModel) -> Result<(), StockpileError> {
+        crane.apply(self, instruction)
+    }
+
+    pub fn move_crate(&mut self, from: usize, to: usize) -> Result<(), StockpileError> {
+        self.move_crates(from, to, 1)
+    }
+
+    /// Move crates one at a time, so they land in reverse order.
+    pub fn move_crates(&mut self, from: usize, to: usize, count: usize) -> Result<(), StockpileError> {
+        self.record(Operation::Crates { from, to, count })
+    }
+
+    /// Move a stack of crates all at once, so they keep their order.
+    pub fn move_stack(&mut self, from: usize, to: usize, count: usize) -> Result<(), StockpileError> {
+        self.record(Operation::Stack { from, to, count })
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Reverse the most recent operation, returning it, or `None` if there is nothing to undo.
+    /// Fails, leaving the piles and history as they were, if the operation cannot be reversed.
+    pub fn undo(&mut self) -> Result<Option<Operation>, StockpileError> {
+        let Some(operation) = self.history.undo() else {
+            return Ok(None);
+        };
+        if let Err(e) = self.perform(operation.inverse()) {
+            self.history.redo();
+            return Err(e);
+        }
+        Ok(Some(operation))
+    }
+
+    /// Apply the most recently undone operation again, returning it, or `None` if there is
+    /// nothing to redo. Fails, leaving the piles and history as they were, if it cannot be
+    /// applied.
+    pub fn redo(&mut self) -> Result<Option<Operation>, StockpileError> {
+        let Some(operation) = self.history.redo() else {
+            return Ok(None);
+        };
+        if let Err(e) = self.perform(operation) {
+            self.history.undo();
+            return Err(e);
+        }
+        Ok(Some(operation))
+    }
+
+    /// The stockpile as it was after the first `step` operations of its history, replayed from
+    /// the drawing it started with.
+    pub fn state_at(&self, step: usize) -> Option<Stockpile> {
+        let operations = self.history.operations().get(..step)?;
+
+        let mut stockpile = Stockpile::new(self.history.origin().to_vec());
+        for operation in operations {
+            stockpile.record(*operation).ok()?;
+        }
+        Some(stockpile)
+    }
+
+    fn record(&mut self, operation: Operation) -> Result<(), StockpileError> {
+        self.perform(operation)?;
+        self.history.record(operation);
+        Ok(())
+    }
+
+    /// Carry out an operation, or fail without touching any pile.
+    fn perform(&mut self, operation: Operation) -> Result<(), StockpileError> {
+        let (Operation::Crates { from, to, count } | Operation::Stack { from, to, count }) = operation;
+
+        for index in [from, to] {
+            if index >= self.piles.len() {
+                return Err(StockpileError::NoSuchPile { index, piles: self.piles.len() });
+            }
+        }
+
+        let available = self.piles[from].items().len();
+        if count > available {
+            return Err(StockpileError::NotEnoughCrates { index: from, requested: count, available });
+        }
+
+        match operation {
+            // One crate at a time, so moving crates onto the pile they came from leaves it as
+            // it was.
+            Operation::Crates { .. } => {
+                for _ in 0..count {
+                    let item = self.piles[from].remove_item().expect("the pile holds enough crates");
+                    self.add_item(item, to);
+                }
+            }
+            Operation::Stack { .. } => {
+                let items = self.piles[from].remove_items(count).expect("the pile holds enough crates");
+                self.add_items(items, to);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_top_item_lables(self) -> Vec<char> {
+        let mut out: Vec<char> = Vec::new();
+        for pile in self.piles() {
+            if let Some(item) = pile.items().last() {
+                out.push(item.label);
+            }
+        }
+        out
+    }
+
+    /// Draw the stockpile the way the puzzle does, one row per line from the top down and
+    /// finishing with the row of pile numbers. The result can be given back to [`initiate`].
+    ///
+    /// [`initiate`]: Stockpile::initiate
+    pub fn to_drawing(&self) -> Vec<String> {
+        let height = self.piles.iter().map(|pile| pile.items().len()).max().unwrap_or(0);
+        let width = (4 * self.piles.len()).saturating_sub(1);
+
+        let mut rows: Vec<String> = (0..height)
+            .rev()
+            .map(|level| {
+                self.piles
+                    .iter()
+                    .map(|pile| match pile.items().get(level) {
+                        Some(item) => format!("[{}]", item.label),
+                        None => "   ".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect();
+
+        let indexes: String = (1..=self.piles.len()).map(|i| format!(" {:<3}", i)).collect();
+        rows.push(format!("{:<width$}", indexes.trim_end()));
+        rows
+    }
+}
+
+/// Stockpiles are equal when their piles hold the same crates, however they got there.
+impl PartialEq for Stockpile {
+    fn eq(&self, other: &Self) -> bool {
+        self.piles == other.piles
+    }
+}
+
+impl Eq for Stockpile {}
+
+impl fmt::Display for Stockpile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_drawing().join("\n"))
+    }
+}
+
+/// Read the row of pile numbers under the drawing, returning the columns each number covers.
+/// Numbers may be any width but must count up from 1.
+fn pile_columns(row: &str) -> common::Result<Vec<Range<usize>>> {
+    let mut columns = vec![];
+
+    for (column, label) in tokens(row) {
+        let expected = columns.len() + 1;
+        if usize::from_str(label).ok() != Some(expected) {
+            return Err(Error::parse(1, column, label, format!("expected pile number {}", expected)));
+        }
+        columns.push(column - 1..column - 1 + label.len());
+    }
+
+    if columns.is_empty() {
+        return Err(Error::parse(1, 1, row, "expected the drawing to end with a row of pile numbers"));
+    }
+
+    Ok(columns)
+}
+
+/// The pile whose number is at, or directly beside, the given column.
+fn pile_under(columns: &[Range<usize>], column: usize) -> Option<usize> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(pile, span)| {
+            let distance = if column < span.start {
+                span.start - column
+            } else {
+                column.saturating_sub(span.end - 1)
+            };
+            (distance, pile)
+        })
+        .min()
+        .filter(|&(distance, _)| distance <= 1)
+        .map(|(_, pile)| pile)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pile {
+    items: Vec<Item>
+}
+
+impl Pile {
+    pub fn new(items: Vec<Item>) -> Self {
+        Self { items }
+    }
+
+    pub fn items(&self) -> &Vec<Item> {
+        &self.items
+    }
+
+    pub fn add_item(&mut self, item: Item) {
+        self.items.push(item);
+    }
+
+    pub fn remove_item(&mut self) -> Option<Item> {
+        self.items.pop()
+    }
+
+    pub fn add_items(&mut self, mut items: Vec<Item>) {
+        while let Some(item) = items.pop() {
+            self.add_item(item);
+        }
+    }
+
+    /// Take `count` crates off the top of the pile, top crate first. Fails without removing
+    /// anything if the pile holds fewer than `count`.
+    pub fn remove_items(&mut self, count: usize) -> Result<Vec<Item>, Underflow> {
+        let available = self.items.len();
+        if count > available {
+            return Err(Underflow { requested: count, available });
+        }
+
+        Ok(self.items.drain(available - count..).rev().collect())
+    }
+}
+
+/// More crates were asked of a pile than it holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Underflow {
+    pub requested: usize,
+    pub available: usize,
+}
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    label: char,
+}
+
+impl Item {
+    pub fn new(label: char) -> Self {
+        Self { label }
+    }
+}
+
+#[cfg(test)]
+// The original tests build their drawings a row at a time.
+#[allow(clippy::vec_init_then_push)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_stockpile_initiate_one_crate_every_row() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let mut start_state: Vec<String> = vec![];
+        start_state.push("[A] [B] [C]".to_string());
+        start_state.push(" 1   2   3".to_string());
+
+        stockpile.initiate(start_state).unwrap();
+
+        dbg!(&stockpile);
+        assert_eq!(stockpile.piles.len(), 3);
+    }
+
+    #[test]
+    fn test_stockpile_initiate_only_adds_to_correct_piles() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let mut start_state: Vec<String> = vec![];
+        start_state.push("[D]     [E]".to_string());
+        start_state.push("[A] [B] [C]".to_string());
+        start_state.push(" 1   2   3".to_string());
+
+        stockpile.initiate(start_state).unwrap();
+
+        dbg!(&stockpile);
+
+        assert_eq!(stockpile.get_pile_len(0).unwrap(), 2);
+        assert_eq!(stockpile.get_pile_len(1).unwrap(), 1);
+        assert_eq!(stockpile.get_pile_len(2).unwrap(), 2);
+
+    }
+
+    #[test]
+    fn test_stockpile_initiate_only_adds_to_correct_pile_only_one_pile_full() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let mut start_state: Vec<String> = vec![];
+        start_state.push("        [E]".to_string());
+        start_state.push("        [D]".to_string());
+        start_state.push("        [C]".to_string());
+        start_state.push("        [B]".to_string());
+        start_state.push("        [A]".to_string());
+        start_state.push(" 1   2   3".to_string());
+
+        stockpile.initiate(start_state).unwrap();
+
+        dbg!(&stockpile);
+
+        assert_eq!(stockpile.get_pile_len(0).unwrap(), 0);
+        assert_eq!(stockpile.get_pile_len(1).unwrap(), 0);
+        assert_eq!(stockpile.get_pile_len(2).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_stockpile_move_single_crate_from_one_pile_to_another() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let mut start_state: Vec<String> = vec![];
+        start_state.push("        [A]".to_string());
+        start_state.push(" 1   2   3".to_string());
+
+        stockpile.initiate(start_state).unwrap();
+
+        dbg!(&stockpile);
+
+        stockpile.move_crate(2, 0).unwrap();
+
+        dbg!(&stockpile);
+
+        assert_eq!(stockpile.get_pile_len(0).unwrap(), 1);
+        assert_eq!(stockpile.get_pile_len(1).unwrap(), 0);
+        assert_eq!(stockpile.get_pile_len(2).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_stockpile_move_from_one_pile_to_another_inverts_pile_order() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let mut start_state: Vec<String> = vec![];
+        start_state.push("[B]        ".to_string());
+        start_state.push("[A]        ".to_string());
+        start_state.push(" 1   2   3".to_string());
+
+        stockpile.initiate(start_state).unwrap();
+
+        assert_eq!(stockpile.get_pile(0).unwrap().items().first().unwrap().label, 'A');
+        assert_eq!(stockpile.get_pile(0).unwrap().items().last().unwrap().label, 'B');
+
+        dbg!(&stockpile);
+
+        stockpile.move_crate(0, 1).unwrap();
+        stockpile.move_crate(0, 1).unwrap();
+
+        dbg!(&stockpile);
+
+        assert_eq!(stockpile.get_pile_len(0).unwrap(), 0);
+        assert_eq!(stockpile.get_pile_len(1).unwrap(), 2);
+        assert_eq!(stockpile.get_pile_len(2).unwrap(), 0);
+
+        assert_eq!(stockpile.get_pile(1).unwrap().items().first().unwrap().label, 'B');
+        assert_eq!(stockpile.get_pile(1).unwrap().items().last().unwrap().label, 'A');
+    }
+
+    #[test]
+    fn test_stockpile_move_stack_preserves_order() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let mut start_state: Vec<String> = vec![];
+        start_state.push("[B]        ".to_string());
+        start_state.push("[A]        ".to_string());
+        start_state.push(" 1   2   3".to_string());
+
+        stockpile.initiate(start_state).unwrap();
+
+        assert_eq!(stockpile.get_pile(0).unwrap().items().first().unwrap().label, 'A');
+        assert_eq!(stockpile.get_pile(0).unwrap().items().last().unwrap().label, 'B');
+
+        dbg!(&stockpile);
+
+        stockpile.move_stack(0, 1, 2).unwrap();
+
+        dbg!(&stockpile);
+
+        assert_eq!(stockpile.get_pile_len(0).unwrap(), 0);
+        assert_eq!(stockpile.get_pile_len(1).unwrap(), 2);
+        assert_eq!(stockpile.get_pile_len(2).unwrap(), 0);
+
+        assert_eq!(stockpile.get_pile(1).unwrap().items().first().unwrap().label, 'A');
+        assert_eq!(stockpile.get_pile(1).unwrap().items().last().unwrap().label, 'B');
+    }
+
+    #[test]
+    fn test_stockpile_initiate_rejects_missing_pile_numbers() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "[A] [B] [C]".to_string(),
+        ];
+
+        assert_eq!(
+            stockpile.initiate(start_state).err().unwrap().to_string(),
+            "line 1, column 1: expected pile number 1 (found '[A]')"
+        );
+    }
+
+    #[test]
+    fn test_stockpile_validate_rejects_missing_pile() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "[A] [B] [C]".to_string(),
+            " 1   2   3".to_string(),
+        ];
+
+        stockpile.initiate(start_state).unwrap();
+
+        assert_eq!(stockpile.validate(&Instruction::new(1, 0, 2)), Ok(()));
+        assert_eq!(
+            stockpile.validate(&Instruction::new(1, 3, 0)),
+            Err(StockpileError::NoSuchPile { index: 3, piles: 3 })
+        );
+    }
+
+    fn label(i: usize) -> char {
+        (b'A' + (i % 26) as u8) as char
+    }
+
+    /// A drawing of `n` piles in the puzzle's layout, where pile `i` holds `i % 4` crates all
+    /// labelled with the `i`th letter of the alphabet.
+    fn drawing(n: usize) -> Vec<String> {
+        let mut rows: Vec<String> = vec![];
+
+        for height in (1..4).rev() {
+            let row: Vec<String> = (0..n)
+                .map(|i| if i % 4 >= height { format!("[{}]", label(i)) } else { "   ".to_string() })
+                .collect();
+            rows.push(row.join(" ").trim_end().to_string());
+        }
+
+        let indexes: String = (1..=n).map(|i| format!(" {:<3}", i)).collect();
+        rows.push(indexes.trim_end().to_string());
+        rows
+    }
+
+    fn assert_matches_drawing(n: usize) {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        stockpile.initiate(drawing(n)).unwrap();
+
+        assert_eq!(stockpile.piles().len(), n);
+        for i in 0..n {
+            assert_eq!(stockpile.get_pile_len(i).unwrap(), i % 4);
+            if let Some(item) = stockpile.get_pile(i).unwrap().items().last() {
+                assert_eq!(item.label, label(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_stockpile_initiate_ten_piles() {
+        assert_matches_drawing(10);
+    }
+
+    #[test]
+    fn test_stockpile_initiate_twenty_six_piles() {
+        assert_matches_drawing(26);
+    }
+
+    #[test]
+    fn test_stockpile_initiate_one_hundred_piles() {
+        assert_matches_drawing(100);
+    }
+
+    #[test]
+    fn test_stockpile_initiate_right_aligned_pile_numbers() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "                                    [J]".to_string(),
+            "[A]                                 [K]".to_string(),
+            " 1   2   3   4   5   6   7   8   9  10".to_string(),
+        ];
+
+        stockpile.initiate(start_state).unwrap();
+
+        assert_eq!(stockpile.piles().len(), 10);
+        assert_eq!(stockpile.get_pile_len(0).unwrap(), 1);
+        assert_eq!(stockpile.get_pile_len(9).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_stockpile_initiate_ragged_rows() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "    [D]".to_string(),
+            "[N] [C]".to_string(),
+            "[Z] [M] [P]   ".to_string(),
+            " 1   2   3 ".to_string(),
+        ];
+
+        stockpile.initiate(start_state).unwrap();
+
+        assert_eq!(stockpile.get_top_item_lables(), vec!['N', 'D', 'P']);
+    }
+
+    #[test]
+    fn test_stockpile_initiate_rejects_out_of_order_pile_numbers() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "[A] [B] [C]".to_string(),
+            " 1   3   2".to_string(),
+        ];
+
+        assert_eq!(
+            stockpile.initiate(start_state).err().unwrap().to_string(),
+            "line 2, column 6: expected pile number 2 (found '3')"
+        );
+    }
+
+    #[test]
+    fn test_stockpile_initiate_rejects_crate_beyond_last_pile() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "[A] [B] [C] [D]".to_string(),
+            " 1   2   3".to_string(),
+        ];
+
+        assert_eq!(
+            stockpile.initiate(start_state).err().unwrap().to_string(),
+            "line 1, column 13: crate is not above a pile number (found '[D]')"
+        );
+    }
+
+    #[test]
+    fn test_stockpile_to_drawing_matches_puzzle_layout() {
+        let start_state: Vec<String> = vec![
+            "    [D]    ".to_string(),
+            "[N] [C]    ".to_string(),
+            "[Z] [M] [P]".to_string(),
+            " 1   2   3 ".to_string(),
+        ];
+
+        let mut stockpile = Stockpile::new(vec![]);
+        stockpile.initiate(start_state.clone()).unwrap();
+
+        assert_eq!(stockpile.to_drawing(), start_state);
+        assert_eq!(stockpile.to_string(), start_state.join("\n"));
+    }
+
+    #[test]
+    fn test_stockpile_to_drawing_round_trips() {
+        for n in [1, 9, 10, 26, 100] {
+            let mut stockpile = Stockpile::new(vec![]);
+            stockpile.initiate(drawing(n)).unwrap();
+
+            let mut copy = Stockpile::new(vec![]);
+            copy.initiate(stockpile.to_drawing()).unwrap();
+
+            assert_eq!(copy, stockpile);
+        }
+    }
+
+    #[test]
+    fn test_stockpile_to_drawing_after_moves() {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "[B]        ".to_string(),
+            "[A]        ".to_string(),
+            " 1   2   3 ".to_string(),
+        ];
+
+        stockpile.initiate(start_state).unwrap();
+        stockpile.move_crate(0, 2).unwrap();
+
+        assert_eq!(
+            stockpile.to_drawing(),
+            vec![
+                "[A]     [B]".to_string(),
+                " 1   2   3 ".to_string(),
+            ]
+        );
+    }
+
+    fn example() -> Stockpile {
+        let mut stockpile = Stockpile::new(vec![]);
+
+        let start_state: Vec<String> = vec![
+            "    [D]    ".to_string(),
+            "[N] [C]    ".to_string(),
+            "[Z] [M] [P]".to_string(),
+            " 1   2   3 ".to_string(),
+        ];
+
+        stockpile.initiate(start_state).unwrap();
+        stockpile
+    }
+
+    #[test]
+    fn test_stockpile_undo_restores_previous_arrangement() {
+        let mut stockpile = example();
+        let start = stockpile.to_drawing();
+
+        stockpile.move_crates(1, 0, 2).unwrap();
+        stockpile.move_stack(0, 2, 3).unwrap();
+
+        assert_eq!(stockpile.undo(), Ok(Some(Operation::Stack { from: 0, to: 2, count: 3 })));
+        assert_eq!(stockpile.undo(), Ok(Some(Operation::Crates { from: 1, to: 0, count: 2 })));
+        assert_eq!(stockpile.undo(), Ok(None));
+        assert_eq!(stockpile.to_drawing(), start);
+    }
+
+    #[test]
+    fn test_stockpile_redo_reapplies_undone_operation() {
+        let mut stockpile = example();
+
+        stockpile.move_crates(1, 0, 2).unwrap();
+        let moved = stockpile.to_drawing();
+
+        stockpile.undo().unwrap();
+        assert_eq!(stockpile.redo(), Ok(Some(Operation::Crates { from: 1, to: 0, count: 2 })));
+        assert_eq!(stockpile.redo(), Ok(None));
+        assert_eq!(stockpile.to_drawing(), moved);
+    }
+
+    #[test]
+    fn test_stockpile_new_move_discards_redo() {
+        let mut stockpile = example();
+
+        stockpile.move_crate(1, 0).unwrap();
+        stockpile.undo().unwrap();
+        stockpile.move_crate(2, 0).unwrap();
+
+        assert_eq!(stockpile.redo(), Ok(None));
+        assert_eq!(stockpile.history().operations().len(), 1);
+    }
+
+    #[test]
+    fn test_stockpile_state_at_replays_history() {
+        let mut stockpile = example();
+
+        stockpile.move_crate(1, 0).unwrap();
+        let after_one = stockpile.clone();
+        stockpile.move_stack(0, 2, 2).unwrap();
+
+        assert_eq!(stockpile.state_at(0).unwrap(), example());
+        assert_eq!(stockpile.state_at(1).unwrap(), after_one);
+        assert_eq!(stockpile.state_at(2).unwrap(), stockpile);
+        assert_eq!(stockpile.state_at(3), None);
+    }
+
+    #[test]
+    fn test_stockpile_undo_that_cannot_be_reversed_fails() {
+        let mut stockpile = example();
+        stockpile.move_crate(0, 2).unwrap();
+        // Only parsing and recorded operations can move crates, so reaching a state the
+        // history does not know about takes emptying a pile directly.
+        stockpile.piles[2] = Pile::new(vec![]);
+
+        assert_eq!(
+            stockpile.undo(),
+            Err(StockpileError::NotEnoughCrates { index: 2, requested: 1, available: 0 })
+        );
+        assert_eq!(stockpile.history().step(), 1);
+    }
+
+    #[test]
+    fn test_stockpile_failed_move_is_not_recorded() {
+        let mut stockpile = example();
+
+        assert_eq!(stockpile.move_stack(2, 0, 2).is_err(), true);
+
+        assert_eq!(stockpile.history().step(), 0);
+        assert_eq!(stockpile, example());
+    }
+
+    #[test]
+    fn test_pile_remove_items_fails_on_underflow() {
+        let mut pile = Pile::new(vec![Item::new('A'), Item::new('B')]);
+
+        assert_eq!(pile.remove_items(3), Err(Underflow { requested: 3, available: 2 }));
+        assert_eq!(pile.items().len(), 2);
+        assert_eq!(pile.remove_items(2), Ok(vec![Item::new('B'), Item::new('A')]));
+    }
+
+    #[test]
+    fn test_stockpile_move_too_many_crates_leaves_stockpile_untouched() {
+        let mut stockpile = example();
+
+        assert_eq!(
+            stockpile.move_stack(0, 1, 3),
+            Err(StockpileError::NotEnoughCrates { index: 0, requested: 3, available: 2 })
+        );
+        assert_eq!(
+            stockpile.move_crates(2, 0, 2).err().unwrap().to_string(),
+            "cannot move 2 crates from pile 3, it only holds 1"
+        );
+        assert_eq!(stockpile, example());
+        assert_eq!(stockpile.history().step(), 0);
+    }
+
+    #[test]
+    fn test_stockpile_move_onto_the_same_pile_leaves_it_as_it_was() {
+        let mut stockpile = example();
+
+        stockpile.move_crates(0, 0, 2).unwrap();
+        stockpile.move_stack(0, 0, 2).unwrap();
+
+        assert_eq!(stockpile.piles(), example().piles());
+    }
+
+    #[test]
+    fn test_stockpile_move_to_missing_pile_fails() {
+        let mut stockpile = example();
+
+        assert_eq!(
+            stockpile.move_crate(0, 3),
+            Err(StockpileError::NoSuchPile { index: 3, piles: 3 })
+        );
+        assert_eq!(
+            stockpile.move_stack(7, 0, 1),
+            Err(StockpileError::NoSuchPile { index: 7, piles: 3 })
+        );
+        assert_eq!(stockpile, example());
+    }
+}
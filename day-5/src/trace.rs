@@ -0,0 +1,127 @@
+use std::fmt;
+
+use crate::{failed_step, CraneModel, Instruction, Procedure, Stockpile};
+
+/// The stockpile as it stood after a number of steps of the procedure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub step: usize,
+    /// The instruction carried out to reach this frame, `None` for the starting drawing.
+    pub instruction: Option<Instruction>,
+    pub drawing: Vec<String>,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.instruction {
+            Some(instruction) => writeln!(f, "Step {}: {}", self.step, instruction)?,
+            None => writeln!(f, "Step {}: start", self.step)?,
+        }
+        for row in &self.drawing {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Walks through a procedure one instruction at a time, yielding the starting drawing followed
+/// by a frame after every instruction. Stops after the first instruction that fails.
+pub struct Frames<'a> {
+    instructions: &'a [Instruction],
+    crane: &'a dyn CraneModel,
+    stockpile: Stockpile,
+    step: usize,
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(procedure: &'a Procedure, crane: &'a dyn CraneModel) -> Self {
+        Self {
+            instructions: &procedure.instructions,
+            crane,
+            stockpile: procedure.stockpile.clone(),
+            step: 0,
+        }
+    }
+
+    /// The stockpile as of the last frame returned.
+    pub fn stockpile(&self) -> &Stockpile {
+        &self.stockpile
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = common::Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step > self.instructions.len() {
+            return None;
+        }
+
+        let instruction = match self.step {
+            0 => None,
+            step => {
+                let instruction = self.instructions[step - 1];
+                if let Err(e) = self.stockpile.apply(&instruction, self.crane) {
+                    self.step = usize::MAX;
+                    return Some(Err(failed_step(step, &instruction, e)));
+                }
+                Some(instruction)
+            }
+        };
+
+        let frame = Frame {
+            step: self.step,
+            instruction,
+            drawing: self.stockpile.to_drawing(),
+        };
+        self.step += 1;
+        Some(Ok(frame))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CrateMover9001, Day5};
+    use common::Solution;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_frames_start_with_drawing_and_follow_each_instruction() {
+        let procedure = Day5::parse("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\n").unwrap();
+
+        let frames: Vec<String> = Frames::new(&procedure, &CrateMover9001)
+            .map(|frame| frame.unwrap().to_string())
+            .collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                "Step 0: start\n[A] [B]\n 1   2 \n".to_string(),
+                "Step 1: move 1 from 1 to 2\n    [A]\n    [B]\n 1   2 \n".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frames_end_on_rearranged_stockpile() {
+        let procedure =
+            Day5::parse("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 2 from 2 to 1\n").unwrap();
+        let mut frames = Frames::new(&procedure, &CrateMover9001);
+
+        assert_eq!(frames.by_ref().count(), 3);
+        assert_eq!(frames.stockpile().clone().get_top_item_lables(), vec!['A']);
+    }
+
+    #[test]
+    fn test_frames_stop_after_failed_instruction() {
+        let procedure =
+            Day5::parse("[A] [B]\n 1   2 \n\nmove 2 from 1 to 2\nmove 1 from 2 to 1\n").unwrap();
+
+        let frames: Vec<bool> = Frames::new(&procedure, &CrateMover9001)
+            .map(|frame| frame.is_ok())
+            .collect();
+
+        assert_eq!(frames, vec![true, false]);
+    }
+}